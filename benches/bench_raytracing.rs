@@ -13,8 +13,14 @@ use raytracing::{
 /// # Panics
 /// Panics if the Cornell Box scene cannot be created.
 pub fn bench_simple(c: &mut Criterion) {
-    let args =
-        Args { width: 10, height: 10, preview: false, camera: ArgCamera::ThinLens, samples: 4 };
+    let args = Args {
+        width: 10,
+        height: 10,
+        preview: false,
+        camera: ArgCamera::ThinLens,
+        samples: 4,
+        ..Args::default()
+    };
     let scene = CornellBox::new(args.width, args.height, &args).unwrap();
     let renderer = Renderer::new(Box::new(scene), &args);
     c.bench_function("render", |b| b.iter(|| black_box(renderer.render())));
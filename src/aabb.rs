@@ -5,7 +5,10 @@
 
 use nalgebra::Point3;
 
-use crate::ray::Ray;
+use crate::{
+    ray::Ray,
+    stats::{self, Counter},
+};
 
 /// An axis-aligned bounding box defined by minimum and maximum corners.
 ///
@@ -43,6 +46,8 @@ impl Aabb {
     /// `true` if the ray intersects the AABB within [tmin, tmax]
     #[must_use]
     pub fn intersects(&self, r: &Ray, mut tmin: f64, mut tmax: f64) -> bool {
+        stats::increment(Counter::AabbTests);
+
         // Test intersection with each pair of planes
         for i in 0..3 {
             // Calculate t values where ray intersects the slab planes
@@ -16,6 +16,7 @@ use crate::{
     config::bvh::{INTERSECTION_COST, MAX_DEPTH, MIN_PRIMITIVES_FOR_SPLIT, TRAVERSAL_COST},
     geometric_object::Geometry,
     ray::{HitRecord, Ray},
+    stats::{self, Counter},
 };
 
 /// A node in the Bounding Volume Hierarchy tree.
@@ -242,6 +243,7 @@ impl Geometry for Bvh {
         if !self.aabb.intersects(ray, t_min, t_max) {
             return None;
         }
+        stats::increment(Counter::BvhNodes);
 
         // Check left child first
         self.left.intersects(ray, t_min, t_max).map_or_else(
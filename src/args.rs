@@ -1,10 +1,29 @@
 //! Command line argument parsing and configuration for the raytracer.
 
+use std::{ops::Range, path::PathBuf, str::FromStr};
+
 use bpaf::Bpaf;
+use serde::Serialize;
+
+use crate::{
+    camera::{Interpolation, StereoLayout},
+    config::{
+        camera::{
+            DEFAULT_F_NUMBER, DEFAULT_FISHEYE_FOV, DEFAULT_FOCAL_LENGTH, DEFAULT_FOV,
+            DEFAULT_INTEROCULAR_DISTANCE, DEFAULT_ORTHOGRAPHIC_WIDTH,
+            DEFAULT_SPHERICAL_HORIZONTAL_FOV, DEFAULT_SPHERICAL_VERTICAL_FOV,
+        },
+        light::{DEFAULT_SUN_AZIMUTH, DEFAULT_SUN_ELEVATION, DEFAULT_TURBIDITY},
+        render::OUTPUT_FILENAME,
+    },
+    light::LightSelection,
+    renderer::Region,
+};
 
 /// Configuration options for the raytracer
 #[derive(Debug, Clone, Bpaf)]
 #[bpaf(options)]
+#[expect(clippy::struct_excessive_bools, reason = "command line flags")]
 pub struct Args {
     /// Show a quick preview with minimal samples and depth.
     /// This significantly reduces render time for quick feedback.
@@ -22,19 +41,447 @@ pub struct Args {
     #[bpaf(external(arg_camera), fallback(ArgCamera::ThinLens))]
     pub camera: ArgCamera,
 
+    /// Focal length of the thin lens camera in millimetres, sets the aperture with `--f-number`
+    #[bpaf(argument("MM"), fallback(DEFAULT_FOCAL_LENGTH))]
+    pub focal_length: f64,
+
+    /// Aperture of the thin lens camera as an f-number, smaller values blur more
+    #[bpaf(argument("N"), fallback(DEFAULT_F_NUMBER))]
+    pub f_number: f64,
+
+    /// Distance of the thin lens camera's plane in focus, in world units.
+    /// Without it the camera focuses on the object seen at `--autofocus`.
+    #[bpaf(argument("DISTANCE"), optional)]
+    pub focus_distance: Option<f64>,
+
+    /// Pixel X,Y of the output image the thin lens camera focuses on, the center by default.
+    /// Animations keep the distance focused at in the first view.
+    #[bpaf(argument("X,Y"), optional)]
+    pub autofocus: Option<Pixel>,
+
+    /// Number of blades of the thin lens camera's iris, for polygonal bokeh
+    #[bpaf(argument("N"), optional)]
+    pub aperture_blades: Option<u32>,
+
+    /// Counterclockwise rotation of the iris blades, in degrees
+    #[bpaf(argument("DEGREES"), fallback(0.0))]
+    pub aperture_rotation: f64,
+
+    /// Grayscale image used as the thin lens camera's aperture, white transmits light
+    #[bpaf(argument("PATH"), optional)]
+    pub aperture_mask: Option<PathBuf>,
+
+    /// Lens prescription used by the realistic lens camera,
+    /// a table of radius, thickness, IOR and aperture diameter in millimetres per element
+    #[bpaf(argument("PATH"), optional)]
+    pub lens_file: Option<PathBuf>,
+
+    /// Width of the area seen by the orthographic camera, in world units
+    #[bpaf(argument("WIDTH"), fallback(DEFAULT_ORTHOGRAPHIC_WIDTH))]
+    pub orthographic_width: f64,
+
+    /// Field of view of the fisheye camera across its image circle, in degrees
+    #[bpaf(argument("DEGREES"), fallback(DEFAULT_FISHEYE_FOV))]
+    pub fisheye_fov: f64,
+
+    /// Horizontal field of view of the spherical camera, in degrees
+    #[bpaf(argument("DEGREES"), fallback(DEFAULT_SPHERICAL_HORIZONTAL_FOV))]
+    pub spherical_horizontal_fov: f64,
+
+    /// Vertical field of view of the spherical camera, in degrees
+    #[bpaf(argument("DEGREES"), fallback(DEFAULT_SPHERICAL_VERTICAL_FOV))]
+    pub spherical_vertical_fov: f64,
+
+    /// Vertical field of view of the camera, in degrees
+    #[bpaf(argument("DEGREES"), fallback(DEFAULT_FOV))]
+    pub fov: f64,
+
+    /// Interpret `--fov` as the horizontal field of view
+    pub horizontal_fov: bool,
+
+    /// Rotation of the camera about its viewing direction, in degrees
+    #[bpaf(argument("DEGREES"), fallback(0.0))]
+    pub roll: f64,
+
+    /// Camera magnification, values above 1 narrow the field of view
+    #[bpaf(argument("FACTOR"), fallback(1.0))]
+    pub zoom: f64,
+
+    /// Time the camera shutter opens, moving objects are blurred between open and close
+    #[bpaf(argument("TIME"), fallback(0.0))]
+    pub shutter_open: f64,
+
+    /// Time the camera shutter closes
+    #[bpaf(argument("TIME"), fallback(0.0))]
+    pub shutter_close: f64,
+
     /// Number of samples per pixel for antialiasing.
     /// Higher values produce better quality but take longer to render.
     #[bpaf(fallback(16))]
     pub samples: u8,
+
+    /// Denoise the rendered image using albedo, normal and depth feature buffers.
+    pub denoise: bool,
+
+    /// Tracer used for primary rays
+    #[bpaf(external(arg_tracer), fallback(ArgTracer::Whitted))]
+    pub tracer: ArgTracer,
+
+    /// Print ray and intersection statistics as JSON instead of text
+    pub stats_json: bool,
+
+    /// Write a machine-readable JSON report of the run to this path
+    #[bpaf(argument("PATH"), optional)]
+    pub report: Option<PathBuf>,
+
+    /// Only render the window X0,Y0,X1,Y1 of the image.
+    /// Integers are pixels, decimals within [0, 1] are fractions of the image size.
+    #[bpaf(argument("X0,Y0,X1,Y1"), optional)]
+    pub crop: Option<Crop>,
+
+    /// Keep the full image size when cropping and leave pixels outside the window black
+    pub crop_full_frame: bool,
+
+    /// Add a spot light aimed at the large sphere
+    pub spot_light: bool,
+
+    /// Image projected by the spot light like a slide
+    #[bpaf(argument("PATH"), optional)]
+    pub gobo: Option<PathBuf>,
+
+    /// IES profile shaping the spot light's intensity, aimed along its cone
+    #[bpaf(argument("PATH"), optional)]
+    pub spot_light_ies: Option<PathBuf>,
+
+    /// Add a directional light shining like the sun through the open front of the box
+    pub directional_light: bool,
+
+    /// Apparent diameter of the directional light in degrees, softening its shadows.
+    /// The sun is about 0.53 degrees wide.
+    #[bpaf(argument("DEGREES"), fallback(0.0))]
+    pub directional_light_diameter: f64,
+
+    /// Add a point light below the ceiling with this power, in watts (`5W`) or lumens (`3400lm`)
+    #[bpaf(argument("POWER"), optional)]
+    pub point_light: Option<Power>,
+
+    /// Radius of the point light's sphere in world units, larger radii soften its shadows
+    #[bpaf(argument("RADIUS"), fallback(0.0))]
+    pub point_light_radius: f64,
+
+    /// Exponent of the point light's distance falloff, 2 is physically based
+    #[bpaf(argument("EXPONENT"), fallback(2.0))]
+    pub point_light_falloff: f64,
+
+    /// IES profile shaping the point light's intensity, aimed down at the floor.
+    /// The light keeps its power, the profile only redistributes it
+    #[bpaf(argument("PATH"), optional)]
+    pub point_light_ies: Option<PathBuf>,
+
+    /// Light the scene with an equirectangular `.hdr` or `.exr` environment map,
+    /// which is also seen by rays leaving the box
+    #[bpaf(argument("PATH"), optional)]
+    pub environment: Option<PathBuf>,
+
+    /// Radiance scaling factor of the environment map
+    #[bpaf(argument("INTENSITY"), fallback(1.0))]
+    pub environment_intensity: f64,
+
+    /// Rotation of the environment map around the vertical axis in degrees
+    #[bpaf(argument("DEGREES"), fallback(0.0))]
+    pub environment_rotation: f64,
+
+    /// Light the scene with an analytic clear sky and sun instead of an environment map.
+    /// The environment intensity and rotation apply to the sky as well.
+    pub sky: bool,
+
+    /// Angle of the sun above the horizon in degrees
+    #[bpaf(argument("DEGREES"), fallback(DEFAULT_SUN_ELEVATION))]
+    pub sun_elevation: f64,
+
+    /// Angle of the sun around the vertical axis in degrees, 180 is behind the camera
+    #[bpaf(argument("DEGREES"), fallback(DEFAULT_SUN_AZIMUTH))]
+    pub sun_azimuth: f64,
+
+    /// Haziness of the sky, from 2 for a very clear sky to 10 for haze
+    #[bpaf(argument("TURBIDITY"), fallback(DEFAULT_TURBIDITY))]
+    pub turbidity: f64,
+
+    /// Lights sampled at each shading point: all, uniform or power.
+    /// Uniform and power pick `--light-samples` lights, which scales to many lights.
+    #[bpaf(argument("SELECTION"), fallback(LightSelection::All))]
+    pub light_selection: LightSelection,
+
+    /// Number of lights picked per shading point by the uniform and power selections
+    #[bpaf(argument("COUNT"), fallback(1))]
+    pub light_samples: u8,
+
+    /// Path of the output image. With `--frames` it must contain a frame number pattern
+    /// like `%d` or `%04d`, which is replaced by the number of each frame.
+    #[bpaf(argument("PATH"), fallback(OUTPUT_FILENAME.to_string()))]
+    pub output: String,
+
+    /// Render the frames START..END of the animation, END exclusive
+    #[bpaf(argument("START..END"), optional)]
+    pub frames: Option<FrameRange>,
+
+    /// Keyframed camera animation, one `frame eye_x eye_y eye_z lookat_x lookat_y lookat_z fov`
+    /// per line. Overrides the field of view of `--fov`.
+    #[bpaf(argument("PATH"), optional)]
+    pub camera_path: Option<PathBuf>,
+
+    /// Interpolation between camera keyframes, linear or catmull-rom
+    #[bpaf(argument("INTERPOLATION"), fallback(Interpolation::Linear))]
+    pub camera_interpolation: Interpolation,
+
+    /// Render a stereo pair, arranged as side-by-side or anaglyph.
+    /// Side-by-side images are twice as wide as a single view.
+    #[bpaf(argument("LAYOUT"), optional)]
+    pub stereo: Option<StereoLayout>,
+
+    /// Swap the views of a side-by-side pair for transverse (cross-eyed) viewing
+    pub stereo_transverse: bool,
+
+    /// Distance between the eyes of the stereo camera, in world units
+    #[bpaf(argument("DISTANCE"), fallback(DEFAULT_INTEROCULAR_DISTANCE))]
+    pub interocular_distance: f64,
 }
 
 /// Available camera types for rendering
-#[derive(Debug, Clone, Bpaf)]
+#[derive(Debug, Clone, Bpaf, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ArgCamera {
     /// Simple pinhole camera model
     Simple,
     /// Thin lens camera model with depth of field effects
     ThinLens,
+    /// Orthographic camera with parallel projection
+    Orthographic,
+    /// Fisheye camera with a circular image
+    Fisheye,
+    /// Spherical panoramic camera with an equirectangular projection
+    Spherical,
+    /// Camera tracing rays through the lens elements of `--lens-file`
+    RealisticLens,
+}
+
+/// Available tracers: the shaded image or one of the debug visualisations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Bpaf, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArgTracer {
+    /// Whitted-style ray tracing with full material shading
+    Whitted,
+    /// Debug: shading normals mapped to RGB
+    #[bpaf(long("debug-normals"))]
+    Normals,
+    /// Debug: distance to the first hit as grayscale
+    #[bpaf(long("debug-depth"))]
+    Depth,
+    /// Debug: triangle barycentric coordinates mapped to RGB
+    #[bpaf(long("debug-barycentrics"))]
+    Barycentrics,
+    /// Debug: triangle edges drawn over a dark background
+    #[bpaf(long("debug-wireframe"))]
+    Wireframe,
+    /// Debug: false color per material
+    #[bpaf(long("debug-material-id"))]
+    MaterialId,
+    /// Debug: heat map of BVH node visits and primitive tests
+    #[bpaf(long("debug-bvh-heatmap"))]
+    BvhHeatmap,
+}
+
+/// A pixel in output image coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Pixel {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl FromStr for Pixel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split(',')
+            .map(|v| v.trim().parse::<u32>().map_err(|e| format!("Invalid pixel value '{v}': {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let [x, y] = values[..] else {
+            return Err(format!("Pixel '{s}' must have the form X,Y"));
+        };
+        Ok(Self { x, y })
+    }
+}
+
+/// Power of a light, in watts or lumens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Power {
+    Watts(f64),
+    Lumens(f64),
+}
+
+impl FromStr for Power {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (value, power): (&str, fn(f64) -> Self) = if let Some(value) = s.strip_suffix("lm") {
+            (value, Self::Lumens)
+        } else if let Some(value) = s.strip_suffix('W') {
+            (value, Self::Watts)
+        } else {
+            return Err(format!("Power '{s}' must end in W or lm"));
+        };
+        let value =
+            value.trim().parse::<f64>().map_err(|e| format!("Invalid power '{value}': {e}"))?;
+        if value <= 0.0 {
+            return Err(format!("Power '{s}' must be greater than 0"));
+        }
+        Ok(power(value))
+    }
+}
+
+/// A range of animation frames, from `start` to `end` exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FrameRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl FromStr for FrameRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once("..")
+            .ok_or_else(|| format!("Frames '{s}' must have the form START..END"))?;
+        let parse =
+            |v: &str| v.trim().parse::<u32>().map_err(|e| format!("Invalid frame '{v}': {e}"));
+        let (start, end) = (parse(start)?, parse(end)?);
+        if start >= end {
+            return Err(format!("Frame range '{s}' is empty"));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// A crop window in output image coordinates, from `(x0, y0)` to `(x1, y1)` exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Crop {
+    pub x0: f64,
+    pub y0: f64,
+    pub x1: f64,
+    pub y1: f64,
+    /// Whether the coordinates are fractions of the image size instead of pixels
+    pub normalized: bool,
+}
+
+impl FromStr for Crop {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split(',')
+            .map(|v| v.trim().parse::<f64>().map_err(|e| format!("Invalid crop value '{v}': {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let [x0, y0, x1, y1] = values[..] else {
+            return Err(format!("Crop window '{s}' must have the form X0,Y0,X1,Y1"));
+        };
+
+        let normalized = s.contains('.') && values.iter().all(|v| (0.0..=1.0).contains(v));
+        if !normalized && values.iter().any(|v| v.fract() != 0.0 || *v < 0.0) {
+            return Err(format!(
+                "Crop window '{s}' must be non-negative pixels or fractions within [0, 1]"
+            ));
+        }
+        if x0 >= x1 || y0 >= y1 {
+            return Err(format!("Crop window '{s}' is empty"));
+        }
+
+        Ok(Self { x0, y0, x1, y1, normalized })
+    }
+}
+
+impl Crop {
+    /// Resolves the window to a pixel region of a `width` x `height` render.
+    ///
+    /// The saved image is mirrored horizontally (see `save_image` in `main.rs`),
+    /// so the window is mirrored as well to select the pixels the user sees.
+    /// The region is clamped to the image bounds.
+    #[must_use]
+    pub fn region(&self, width: u32, height: u32) -> Region {
+        let (sx, sy) =
+            if self.normalized { (f64::from(width), f64::from(height)) } else { (1.0, 1.0) };
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let clamp = |v: f64, max: u32| (v.round().max(0.0) as u32).min(max);
+        let x0 = clamp(self.x0 * sx, width);
+        let x1 = clamp(self.x1 * sx, width);
+        let y0 = clamp(self.y0 * sy, height);
+        let y1 = clamp(self.y1 * sy, height);
+        Region { x0: width - x1, y0, x1: width - x0, y1 }
+    }
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            preview: false,
+            width: 500,
+            height: 500,
+            camera: ArgCamera::ThinLens,
+            focal_length: DEFAULT_FOCAL_LENGTH,
+            f_number: DEFAULT_F_NUMBER,
+            focus_distance: None,
+            autofocus: None,
+            aperture_blades: None,
+            aperture_rotation: 0.0,
+            aperture_mask: None,
+            lens_file: None,
+            orthographic_width: DEFAULT_ORTHOGRAPHIC_WIDTH,
+            fisheye_fov: DEFAULT_FISHEYE_FOV,
+            spherical_horizontal_fov: DEFAULT_SPHERICAL_HORIZONTAL_FOV,
+            spherical_vertical_fov: DEFAULT_SPHERICAL_VERTICAL_FOV,
+            fov: DEFAULT_FOV,
+            horizontal_fov: false,
+            roll: 0.0,
+            zoom: 1.0,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            samples: 16,
+            denoise: false,
+            tracer: ArgTracer::Whitted,
+            stats_json: false,
+            report: None,
+            crop: None,
+            crop_full_frame: false,
+            spot_light: false,
+            gobo: None,
+            spot_light_ies: None,
+            directional_light: false,
+            directional_light_diameter: 0.0,
+            point_light: None,
+            point_light_radius: 0.0,
+            point_light_falloff: 2.0,
+            point_light_ies: None,
+            environment: None,
+            environment_intensity: 1.0,
+            environment_rotation: 0.0,
+            sky: false,
+            sun_elevation: DEFAULT_SUN_ELEVATION,
+            sun_azimuth: DEFAULT_SUN_AZIMUTH,
+            turbidity: DEFAULT_TURBIDITY,
+            light_selection: LightSelection::All,
+            light_samples: 1,
+            output: OUTPUT_FILENAME.to_string(),
+            frames: None,
+            camera_path: None,
+            camera_interpolation: Interpolation::Linear,
+            stereo: None,
+            stereo_transverse: false,
+            interocular_distance: DEFAULT_INTEROCULAR_DISTANCE,
+        }
+    }
 }
 
 impl Args {
@@ -51,11 +498,142 @@ impl Args {
             return Err("Sample count must be greater than 0".to_string());
         }
 
+        if self.light_samples == 0 {
+            return Err("Light sample count must be greater than 0".to_string());
+        }
+
         // Reasonable limits to prevent excessive memory usage
         if self.width > 8192 || self.height > 8192 {
             return Err("Width and height must be 8192 or less".to_string());
         }
 
+        if !(self.fov > 0.0 && self.fov < 180.0) {
+            return Err("Field of view must be between 0 and 180 degrees".to_string());
+        }
+
+        if self.orthographic_width <= 0.0 {
+            return Err("Orthographic width must be greater than 0".to_string());
+        }
+
+        if !(self.fisheye_fov > 0.0 && self.fisheye_fov <= 360.0) {
+            return Err("Fisheye field of view must be between 0 and 360 degrees".to_string());
+        }
+
+        if !(self.spherical_horizontal_fov > 0.0 && self.spherical_horizontal_fov <= 360.0) {
+            return Err(
+                "Spherical horizontal field of view must be between 0 and 360 degrees".to_string()
+            );
+        }
+
+        if !(self.spherical_vertical_fov > 0.0 && self.spherical_vertical_fov <= 180.0) {
+            return Err(
+                "Spherical vertical field of view must be between 0 and 180 degrees".to_string()
+            );
+        }
+
+        if self.zoom <= 0.0 {
+            return Err("Zoom must be greater than 0".to_string());
+        }
+
+        if self.shutter_close < self.shutter_open {
+            return Err("Shutter must not close before it opens".to_string());
+        }
+
+        if self.focal_length <= 0.0 || self.f_number <= 0.0 {
+            return Err("Focal length and f-number must be greater than 0".to_string());
+        }
+
+        if self.focus_distance.is_some_and(|distance| distance <= 0.0) {
+            return Err("Focus distance must be greater than 0".to_string());
+        }
+
+        if self.aperture_blades.is_some_and(|blades| blades < 3) {
+            return Err("Aperture must have at least 3 blades".to_string());
+        }
+
+        if self.aperture_blades.is_some() && self.aperture_mask.is_some() {
+            return Err("Aperture blades and mask cannot be combined".to_string());
+        }
+
+        if let Some(pixel) = &self.autofocus
+            && (pixel.x >= self.width || pixel.y >= self.height)
+        {
+            return Err("Autofocus pixel must lie inside the image".to_string());
+        }
+
+        if let Some(crop) = &self.crop
+            && crop.region(self.width, self.height).is_empty()
+        {
+            return Err("Crop window must overlap the image".to_string());
+        }
+
+        if self.gobo.is_some() && !self.spot_light {
+            return Err("A gobo needs the spot light".to_string());
+        }
+
+        if self.spot_light_ies.is_some() && !self.spot_light {
+            return Err("A spot light IES profile needs the spot light".to_string());
+        }
+
+        if self.point_light_ies.is_some() && self.point_light.is_none() {
+            return Err("A point light IES profile needs the point light".to_string());
+        }
+
+        if !(0.0..180.0).contains(&self.directional_light_diameter) {
+            return Err("Directional light diameter must be between 0 and 180 degrees".to_string());
+        }
+
+        if self.point_light_radius < 0.0 || self.point_light_falloff < 0.0 {
+            return Err("Point light radius and falloff must not be negative".to_string());
+        }
+
+        if self.environment_intensity < 0.0 {
+            return Err("Environment intensity must not be negative".to_string());
+        }
+
+        if self.sky && self.environment.is_some() {
+            return Err("Use either the sky or an environment map".to_string());
+        }
+
+        if !(0.0..=90.0).contains(&self.sun_elevation) {
+            return Err("Sun elevation must be between 0 and 90 degrees".to_string());
+        }
+
+        if !(2.0..=10.0).contains(&self.turbidity) {
+            return Err("Turbidity must be between 2 and 10".to_string());
+        }
+
+        if self.frames().len() > 1 && format_frame(&self.output, 0).is_none() {
+            return Err("Output must contain a frame number pattern like %04d".to_string());
+        }
+
         Ok(())
     }
+
+    /// Returns the frames to render, frame 0 unless `--frames` is given
+    #[must_use]
+    pub fn frames(&self) -> Range<u32> {
+        self.frames.map_or(0..1, |frames| frames.start..frames.end)
+    }
+
+    /// Returns the output path of `frame`, with its number replacing the pattern in `--output`
+    #[must_use]
+    pub fn output_path(&self, frame: u32) -> String {
+        format_frame(&self.output, frame).unwrap_or_else(|| self.output.clone())
+    }
+}
+
+/// Replaces the first `%d` or zero padded `%0Nd` pattern in `pattern` with `frame`.
+///
+/// Returns `None` if `pattern` has no frame number pattern.
+fn format_frame(pattern: &str, frame: u32) -> Option<String> {
+    pattern.match_indices('%').find_map(|(start, _)| {
+        let rest = &pattern[start + 1..];
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        if !rest[digits..].starts_with('d') || (digits > 0 && !rest.starts_with('0')) {
+            return None;
+        }
+        let width = rest[..digits].parse::<usize>().unwrap_or(0);
+        Some(format!("{}{frame:0width$}{}", &pattern[..start], &rest[digits + 1..]))
+    })
 }
@@ -1,15 +1,26 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use nalgebra::Point3;
+use nalgebra::{Point2, Point3};
 use tobj::{LoadOptions, load_obj};
 
 use crate::{
-    brdf::Lambertian,
+    brdf::{GlossySpecular, Lambertian, PerfectSpecular},
     color::Color,
+    config::material::{
+        DEFAULT_AMBIENT_KD, DEFAULT_DIFFUSE_KD, DEFAULT_MATERIAL_COLOR, DEFAULT_SHININESS,
+        MAX_REFLECTANCE,
+    },
     error::{RayTracingError, Result},
     geometric_object::{Geometry, Triangle},
     light::{Area, Light},
-    material::{Emissive, Matte},
+    material::{
+        BumpMapped, Dielectric, Emissive, Material, Matte, Phong, Reflective, Textured, Tint,
+    },
+    texture::ImageTexture,
 };
 
 pub struct Object {
@@ -24,16 +35,84 @@ pub struct Asset {
     pub lights: Vec<Arc<dyn Light>>,
 }
 
+/// Settings for translating the materials of a model file.
+#[derive(Clone)]
+pub struct AssetOptions {
+    /// Treat materials with an ambient red component above 1 as emitters of that strength
+    /// and their diffuse color, the convention of older model files like the bundled
+    /// Cornell box. `Ke` emission is read either way.
+    pub legacy_ambient_emission: bool,
+    /// Material of meshes that do not use one from the MTL file
+    pub default_material: Arc<dyn Material>,
+}
+
+impl Default for AssetOptions {
+    fn default() -> Self {
+        let color = Color::from(DEFAULT_MATERIAL_COLOR);
+        Self {
+            legacy_ambient_emission: false,
+            default_material: Arc::new(Matte::new(
+                Lambertian::new(DEFAULT_AMBIENT_KD, color),
+                Lambertian::new(DEFAULT_DIFFUSE_KD, color),
+            )),
+        }
+    }
+}
+
+/// Image textures of a model, loaded once each
+struct Textures {
+    /// Directory that texture file names are relative to
+    directory: PathBuf,
+    loaded: HashMap<PathBuf, Arc<ImageTexture>>,
+}
+
+impl Textures {
+    /// Returns the texture in a file, loading it on first use.
+    fn get(&mut self, file_name: &str) -> Result<Arc<ImageTexture>> {
+        let path = self.directory.join(file_name);
+        if let Some(texture) = self.loaded.get(&path) {
+            return Ok(Arc::clone(texture));
+        }
+        let texture = Arc::new(ImageTexture::from_file(&path)?);
+        self.loaded.insert(path, Arc::clone(&texture));
+        Ok(texture)
+    }
+}
+
 impl Asset {
     /// Creates a new asset from a 3D model file.
     ///
+    /// MTL materials are translated to the crate's materials:
+    /// - a nonzero `Ke` emission color gives an emitter and an area light, scaled by the
+    ///   nonstandard `emission_strength` statement if present
+    /// - a dissolve `d` below 1 or a transparent illumination model (4, 6, 7 or 9) gives
+    ///   a [`Dielectric`] with index of refraction `Ni` and filter color `Tf`
+    /// - illumination model 3 gives a [`Reflective`] mirroring with `Ks`
+    /// - other materials with a `Ks` color give a [`Phong`] with exponent `Ns`, unless
+    ///   the illumination model 0 or 1 turns highlights off
+    /// - the rest are [`Matte`]
+    ///
+    /// A `map_Kd` texture wraps the material in a [`Textured`], which colors the diffuse
+    /// lobe, or the filter color of a [`Dielectric`].
+    /// A `map_bump` or `bump` texture wraps the material in a [`BumpMapped`], scaled by
+    /// its `-bm` option. Texture files are relative to the model file. Meshes without
+    /// a material get a gray matte one.
+    ///
     /// # Arguments
     /// * `file_name` - Path to the 3D model file
     /// * `scale` - Scale factor to apply to the loaded geometry
     ///
     /// # Errors
-    /// Returns an error if the file cannot be loaded or parsed
+    /// Returns an error if the file or one of its textures cannot be loaded or parsed
     pub fn new(file_name: &str, scale: f64) -> Result<Self> {
+        Self::with_options(file_name, scale, &AssetOptions::default())
+    }
+
+    /// Creates a new asset from a 3D model file, translating its materials with `options`.
+    ///
+    /// # Errors
+    /// Returns an error if the file or one of its textures cannot be loaded or parsed
+    pub fn with_options(file_name: &str, scale: f64, options: &AssetOptions) -> Result<Self> {
         let mut asset = Self { objects: vec![], geometries: vec![], lights: vec![] };
 
         let (models, materials) =
@@ -43,69 +122,160 @@ impl Asset {
                 })?;
 
         let materials = materials.unwrap_or_default();
+        let directory = Path::new(file_name).parent().unwrap_or_else(|| Path::new(""));
+        let mut textures = Textures { directory: directory.to_path_buf(), loaded: HashMap::new() };
 
         for model in &models {
             let mesh = &model.mesh;
-            let mut vertices: Vec<Point3<f64>> = vec![];
-            for v in 0..mesh.positions.len() / 3 {
-                vertices.push(Point3::new(
-                    f64::from(mesh.positions[3 * v]),
-                    f64::from(mesh.positions[3 * v + 1]),
-                    f64::from(mesh.positions[3 * v + 2]),
-                ));
-            }
+            let vertices: Vec<Point3<f64>> = mesh
+                .positions
+                .chunks_exact(3)
+                .map(|p| Point3::new(f64::from(p[0]), f64::from(p[1]), f64::from(p[2])))
+                .collect();
+            // Image rows run down from the top, texture coordinates up from the bottom
+            let texcoords: Vec<Point2<f64>> = mesh
+                .texcoords
+                .chunks_exact(2)
+                .map(|t| Point2::new(f64::from(t[0]), 1.0 - f64::from(t[1])))
+                .collect();
+            let has_texcoords = mesh.texcoord_indices.len() == mesh.indices.len();
 
-            let mut triangles: Vec<Arc<dyn Geometry>> = vec![];
-
-            match mesh.material_id {
-                None => {}
+            let (material, emission): (Arc<dyn Material>, _) = match mesh.material_id {
+                None => (Arc::clone(&options.default_material), None),
                 Some(material_id) => {
                     let m = &materials[material_id];
-                    let ambient = m.ambient.unwrap_or_default();
-                    let ambient_color = Color::new(
-                        f64::from(ambient[0]),
-                        f64::from(ambient[1]),
-                        f64::from(ambient[2]),
-                    );
-
-                    let diffuse = m.diffuse.unwrap_or_default();
-                    let diffuse_color = Color::new(
-                        f64::from(diffuse[0]),
-                        f64::from(diffuse[1]),
-                        f64::from(diffuse[2]),
-                    );
-
-                    for f in 0..(mesh.indices.len() / 3) {
-                        let start = f * 3;
-                        let face_indices: Vec<_> = mesh.indices[start..start + 3].iter().collect();
-                        let v1 = vertices[*face_indices[0] as usize];
-                        let v2 = vertices[*face_indices[1] as usize];
-                        let v3 = vertices[*face_indices[2] as usize];
-
-                        let triangle: Arc<dyn Geometry> = if ambient[0] > 1.0 {
-                            let material = Emissive::new(f64::from(ambient[0]), diffuse_color);
-                            Arc::new(Triangle::new(material, v1, v2, v3, scale))
-                        } else {
-                            let ambient_brdf = Lambertian::new(0.5, ambient_color);
-                            let diffuse_brdf = Lambertian::new(1.0, diffuse_color);
-                            let material = Matte::new(ambient_brdf, diffuse_brdf);
-                            Arc::new(Triangle::new(material, v1, v2, v3, scale))
-                        };
-
-                        triangles.push(triangle);
-                    }
-
-                    if ambient[0] > 1.0 {
-                        let emissive = Emissive::new(f64::from(ambient[0]), diffuse_color);
-                        let arealight = Arc::new(Area::new(triangles.clone(), emissive));
-                        asset.lights.push(arealight);
+                    match emission(m, options) {
+                        Some(emissive) => (Arc::new(emissive), Some(emissive)),
+                        None => (translate(m, &mut textures)?, None),
                     }
+                }
+            };
 
-                    asset.geometries.extend(triangles);
+            let mut triangles: Vec<Arc<dyn Geometry>> = vec![];
+            for (face, indices) in mesh.indices.chunks_exact(3).enumerate() {
+                let [v1, v2, v3] = [0, 1, 2].map(|i| vertices[indices[i] as usize]);
+                let mut triangle = Triangle::new(Arc::clone(&material), v1, v2, v3, scale);
+                if has_texcoords {
+                    let start = face * 3;
+                    triangle = triangle.with_texcoords(
+                        [0, 1, 2].map(|i| texcoords[mesh.texcoord_indices[start + i] as usize]),
+                    );
                 }
+                triangles.push(Arc::new(triangle));
             }
+
+            if let Some(emissive) = emission {
+                let arealight = Arc::new(Area::new(triangles.clone(), emissive));
+                asset.lights.push(arealight);
+            }
+
+            asset.geometries.extend(triangles);
         }
 
         Ok(asset)
     }
 }
+
+/// Returns the emitter described by an MTL material, if it emits light.
+///
+/// A nonzero `Ke` color, scaled by `emission_strength`, takes precedence over
+/// the legacy ambient convention.
+fn emission(m: &tobj::Material, options: &AssetOptions) -> Option<Emissive> {
+    if let Some(ke) = m.emissive.map(color)
+        && ke.max() > 0.0
+    {
+        let strength = m.unknown_param.get("emission_strength").and_then(|s| s.trim().parse().ok());
+        return Some(Emissive::new(strength.unwrap_or(1.0), ke));
+    }
+    let ambient = m.ambient.unwrap_or_default();
+    (options.legacy_ambient_emission && ambient[0] > 1.0)
+        .then(|| Emissive::new(f64::from(ambient[0]), color(m.diffuse.unwrap_or_default())))
+}
+
+/// Translates a non emitting MTL material to the crate's materials.
+fn translate(m: &tobj::Material, textures: &mut Textures) -> Result<Arc<dyn Material>> {
+    let ka = m.ambient.map_or_else(Color::zeros, color);
+    let kd = m.diffuse.map_or_else(Color::zeros, color);
+    let ks = m.specular.map_or_else(Color::zeros, color);
+    let illum = m.illumination_model;
+    let transparent = m.dissolve.is_some_and(|d| d < 1.0) || matches!(illum, Some(4 | 6 | 7 | 9));
+
+    // The strength of the specular color goes into the coefficient, its hue into the color
+    let ks_max = ks.max().clamp(0.0, MAX_REFLECTANCE);
+    let cs = if ks_max > 0.0 { ks / ks.max() } else { Color::repeat(1.0) };
+    let specular_brdf =
+        GlossySpecular::new(ks_max, m.shininess.map_or(DEFAULT_SHININESS, f64::from), cs);
+    let ambient_brdf = Lambertian::new(DEFAULT_AMBIENT_KD, ka);
+
+    let texture = m
+        .diffuse_texture
+        .as_ref()
+        .map(|statement| textures.get(texture_statement(statement).1))
+        .transpose()?;
+
+    let mut material = if transparent {
+        let ior = m.optical_density.map_or(1.0, f64::from);
+        let cf = m.unknown_param.get("Tf").and_then(|tf| parse_color(tf));
+        let cf = cf.unwrap_or_else(|| Color::repeat(1.0));
+        with_texture(Dielectric::new(ior, cf, specular_brdf), texture)
+    } else if illum == Some(3) {
+        let diffuse_brdf = Lambertian::new(DEFAULT_DIFFUSE_KD, kd);
+        let reflective_brdf = PerfectSpecular::new(ks_max, cs);
+        with_texture(
+            Reflective::new(ambient_brdf, diffuse_brdf, specular_brdf, reflective_brdf),
+            texture,
+        )
+    } else if ks_max > 0.0 && !matches!(illum, Some(0 | 1)) {
+        let diffuse_brdf = Lambertian::new(MAX_REFLECTANCE - ks_max, kd);
+        with_texture(Phong::new(ambient_brdf, diffuse_brdf, specular_brdf), texture)
+    } else {
+        with_texture(Matte::new(ambient_brdf, Lambertian::new(DEFAULT_DIFFUSE_KD, kd)), texture)
+    };
+
+    if let Some(statement) = &m.normal_texture {
+        let (strength, file_name) = texture_statement(statement);
+        material = Arc::new(BumpMapped::new(material, textures.get(file_name)?, strength));
+    }
+    Ok(material)
+}
+
+/// Wraps a material in [`Textured`] if it has a diffuse texture.
+fn with_texture<M: Material + Tint + 'static>(
+    material: M,
+    texture: Option<Arc<ImageTexture>>,
+) -> Arc<dyn Material> {
+    match texture {
+        Some(texture) => Arc::new(Textured::new(material, texture)),
+        None => Arc::new(material),
+    }
+}
+
+fn color(c: [f32; 3]) -> Color {
+    Color::new(f64::from(c[0]), f64::from(c[1]), f64::from(c[2]))
+}
+
+/// Parses an MTL color statement of one or three numbers.
+fn parse_color(statement: &str) -> Option<Color> {
+    let values: Option<Vec<f64>> = statement.split_whitespace().map(|v| v.parse().ok()).collect();
+    match *values?.as_slice() {
+        [v] => Some(Color::repeat(v)),
+        [r, g, b] => Some(Color::new(r, g, b)),
+        _ => None,
+    }
+}
+
+/// Splits a texture map statement into its bump multiplier and file name.
+///
+/// The file name is the last token, so it may not contain spaces. Other options are ignored.
+///
+/// # Returns
+/// The value of the `-bm` option, 1 if absent, and the file name
+fn texture_statement(statement: &str) -> (f64, &str) {
+    let tokens: Vec<&str> = statement.split_whitespace().collect();
+    let strength = tokens
+        .windows(2)
+        .find(|pair| pair[0] == "-bm")
+        .and_then(|pair| pair[1].parse().ok())
+        .unwrap_or(1.0);
+    (strength, tokens.last().copied().unwrap_or_default())
+}
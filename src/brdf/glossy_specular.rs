@@ -1,7 +1,12 @@
+use std::f64::consts::PI;
+
+use nalgebra::Point2;
+
 use super::Brdf;
-use crate::{color::Color, model::Vec3, ray::Hit};
+use crate::{color::Color, model::Vec3, ray::Hit, sampler::Sampler};
 
 /// Shows specular highlights (shiny white dot) on surfaces
+#[derive(Clone, Copy)]
 pub struct GlossySpecular {
     /// specular reflection coefficient [0, 1]
     pub ks: f64,
@@ -54,4 +59,28 @@ impl Brdf for GlossySpecular {
         *pdf = phong_lobe * hit.normal.dot(wi);
         self.cs * self.ks * phong_lobe
     }
+
+    /// Distributed like the Phong lobe around the mirror direction, `cos^exp` of the angle to it
+    fn sample_wi(&self, hit: &Hit, u: Point2<f64>) -> Option<Vec3> {
+        let r = mirror(hit);
+        let u_ = Vec3::new(0.00424, 1.0, 0.00764).cross(&r).normalize();
+        let v = u_.cross(&r);
+        let sp = Sampler::cosine_power(u, self.exp);
+        let wi = (sp.x * u_ + sp.y * v + sp.z * r).normalize();
+        (hit.normal.dot(&wi) > 0.0).then_some(wi)
+    }
+
+    fn pdf(&self, hit: &Hit, wi: &Vec3) -> f64 {
+        let rdotwi = mirror(hit).dot(wi);
+        if rdotwi <= 0.0 {
+            return 0.0;
+        }
+        (self.exp + 1.0) / (2.0 * PI) * rdotwi.powf(self.exp)
+    }
+}
+
+/// Returns the direction of mirror reflection of the ray at the hit point.
+fn mirror(hit: &Hit) -> Vec3 {
+    let wo = -hit.ray.dir;
+    (hit.normal * (2.0 * hit.normal.dot(&wo)) - wo).normalize()
 }
@@ -1,8 +1,11 @@
 use std::f64::consts::FRAC_1_PI;
 
+use nalgebra::Point2;
+
 use super::Brdf;
-use crate::{color::Color, model::Vec3, ray::Hit};
+use crate::{color::Color, model::Vec3, ray::Hit, sampler::Sampler};
 
+#[derive(Clone, Copy)]
 pub struct Lambertian {
     /// diffuse reflection coefficient [0, 1]
     pub kd: f64,
@@ -16,6 +19,12 @@ impl Lambertian {
     pub const fn new(kd: f64, cd: Color) -> Self {
         Self { kd, cd }
     }
+
+    /// Returns the brdf with its color multiplied by `color`.
+    #[must_use]
+    pub fn tinted(&self, color: &Color) -> Self {
+        Self::new(self.kd, self.cd.component_mul(color))
+    }
 }
 
 impl Brdf for Lambertian {
@@ -39,4 +48,17 @@ impl Brdf for Lambertian {
         *pdf = hit.normal.dot(wi) * FRAC_1_PI;
         self.kd * self.cd * FRAC_1_PI
     }
+
+    /// Cosine weighted around the normal
+    fn sample_wi(&self, hit: &Hit, u: Point2<f64>) -> Option<Vec3> {
+        let w = hit.normal;
+        let v = Vec3::new(0.0034, 1.0, 0.0071).cross(&w).normalize();
+        let u_ = v.cross(&w);
+        let sp = Sampler::cosine_power(u, 1.0);
+        Some((sp.x * u_ + sp.y * v + sp.z * w).normalize())
+    }
+
+    fn pdf(&self, hit: &Hit, wi: &Vec3) -> f64 {
+        hit.normal.dot(wi).max(0.0) * FRAC_1_PI
+    }
 }
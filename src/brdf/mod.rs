@@ -6,6 +6,8 @@ pub use glossy_specular::*;
 pub use lambertian::*;
 pub use perfect_specular::*;
 
+use nalgebra::Point2;
+
 use crate::{color::Color, model::Vec3, ray::Hit};
 
 /// Bidrectional Reflectance Distribution Function
@@ -26,4 +28,16 @@ pub trait Brdf {
     fn sample_f(&self, _hit: &Hit, _wi: &mut Vec3, _pdf: &mut f64) -> Vec3 {
         Color::zeros()
     }
+
+    /// Maps a point in the unit square to an incoming direction distributed like [`Brdf::pdf`],
+    /// used to importance sample direct lighting.
+    /// Returns `None` if the brdf cannot be sampled or the direction is below the surface
+    fn sample_wi(&self, _hit: &Hit, _u: Point2<f64>) -> Option<Vec3> {
+        None
+    }
+
+    /// Solid angle density of [`Brdf::sample_wi`] choosing `wi`
+    fn pdf(&self, _hit: &Hit, _wi: &Vec3) -> f64 {
+        0.0
+    }
 }
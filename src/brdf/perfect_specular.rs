@@ -1,6 +1,7 @@
 use super::Brdf;
 use crate::{color::Color, model::Vec3, ray::Hit};
 
+#[derive(Clone, Copy)]
 pub struct PerfectSpecular {
     /// reflection coefficient
     pub kr: f64,
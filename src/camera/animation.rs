@@ -0,0 +1,189 @@
+use std::{fs, path::Path, str::FromStr};
+
+use serde::Serialize;
+
+use crate::{
+    error::{RayTracingError, Result},
+    model::Pot3,
+};
+
+/// Interpolation between the keyframes of a camera path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Interpolation {
+    /// Straight lines between keyframes, with a kink at every keyframe
+    #[default]
+    Linear,
+    /// A Catmull-Rom spline passing smoothly through every keyframe
+    CatmullRom,
+}
+
+impl FromStr for Interpolation {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(Self::Linear),
+            "catmull-rom" => Ok(Self::CatmullRom),
+            _ => Err(format!("Unknown interpolation '{s}', expected linear or catmull-rom")),
+        }
+    }
+}
+
+/// Camera framing at a frame of an animation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraKey {
+    /// Frame number of the keyframe
+    pub frame: f64,
+    /// Position of the camera
+    pub eye: Pot3,
+    /// Point the camera looks at
+    pub lookat: Pot3,
+    /// Field of view in degrees, vertical unless the camera uses a horizontal field of view
+    pub fov: f64,
+}
+
+/// A keyframed camera animation.
+///
+/// Between keyframes the eye, look-at point and field of view are interpolated,
+/// before the first and after the last keyframe the camera holds still.
+#[derive(Debug, Clone)]
+pub struct CameraPath {
+    /// Keyframes sorted by frame, never empty
+    keys: Vec<CameraKey>,
+    interpolation: Interpolation,
+}
+
+impl CameraPath {
+    /// Creates a camera path from keyframes, in any order.
+    ///
+    /// # Errors
+    /// Returns an error if there are no keyframes
+    pub fn new(mut keys: Vec<CameraKey>, interpolation: Interpolation) -> Result<Self> {
+        if keys.is_empty() {
+            return Err(RayTracingError::AssetError("Camera path has no keyframes".to_string()));
+        }
+        keys.sort_by(|a, b| a.frame.total_cmp(&b.frame));
+        Ok(Self { keys, interpolation })
+    }
+
+    /// Loads a camera path from a file.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed, see [`CameraPath::parse`]
+    pub fn from_file(path: &Path, interpolation: Interpolation) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            RayTracingError::AssetError(format!(
+                "Failed to load camera path '{}': {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&text, interpolation)
+    }
+
+    /// Parses a camera path with one keyframe per line.
+    ///
+    /// Each line holds the frame, the eye and look-at positions and the field of view:
+    /// `frame eye_x eye_y eye_z lookat_x lookat_y lookat_z fov`.
+    /// Blank lines and lines starting with `#` are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if a line is malformed or there are no keyframes
+    pub fn parse(text: &str, interpolation: Interpolation) -> Result<Self> {
+        let keys = text
+            .lines()
+            .enumerate()
+            .map(|(n, line)| (n + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(n, line)| {
+                let values = line
+                    .split_whitespace()
+                    .map(str::parse::<f64>)
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|e| {
+                        RayTracingError::AssetError(format!("Camera path line {n}: {e}"))
+                    })?;
+                let [frame, ex, ey, ez, lx, ly, lz, fov] = values[..] else {
+                    return Err(RayTracingError::AssetError(format!(
+                        "Camera path line {n}: expected 8 values, found {}",
+                        values.len()
+                    )));
+                };
+                Ok(CameraKey {
+                    frame,
+                    eye: Pot3::new(ex, ey, ez),
+                    lookat: Pot3::new(lx, ly, lz),
+                    fov,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(keys, interpolation)
+    }
+
+    /// Returns the keyframes sorted by frame.
+    #[must_use]
+    pub fn keys(&self) -> &[CameraKey] {
+        &self.keys
+    }
+
+    /// Returns the interpolated camera framing at `frame`.
+    #[must_use]
+    pub fn at(&self, frame: f64) -> CameraKey {
+        let next = self.keys.partition_point(|k| k.frame <= frame);
+        if next == 0 {
+            return self.keys[0];
+        }
+        if next == self.keys.len() {
+            return self.keys[next - 1];
+        }
+        let (i, j) = (next - 1, next);
+        let t = (frame - self.keys[i].frame) / (self.keys[j].frame - self.keys[i].frame);
+        match self.interpolation {
+            Interpolation::Linear => {
+                let (a, b) = (&self.keys[i], &self.keys[j]);
+                CameraKey {
+                    frame,
+                    eye: a.eye + (b.eye - a.eye) * t,
+                    lookat: a.lookat + (b.lookat - a.lookat) * t,
+                    fov: (b.fov - a.fov).mul_add(t, a.fov),
+                }
+            }
+            Interpolation::CatmullRom => {
+                // The end keyframes are repeated to give the outer segments a tangent
+                let p0 = &self.keys[i.saturating_sub(1)];
+                let p3 = &self.keys[(j + 1).min(self.keys.len() - 1)];
+                let (p1, p2) = (&self.keys[i], &self.keys[j]);
+                CameraKey {
+                    frame,
+                    eye: catmull_rom(p0.eye.coords, p1.eye.coords, p2.eye.coords, p3.eye.coords, t)
+                        .into(),
+                    lookat: catmull_rom(
+                        p0.lookat.coords,
+                        p1.lookat.coords,
+                        p2.lookat.coords,
+                        p3.lookat.coords,
+                        t,
+                    )
+                    .into(),
+                    fov: catmull_rom(p0.fov, p1.fov, p2.fov, p3.fov, t),
+                }
+            }
+        }
+    }
+}
+
+/// Evaluates the uniform Catmull-Rom spline through `p1` and `p2` at `t` in [0, 1].
+fn catmull_rom<T>(p0: T, p1: T, p2: T, p3: T, t: f64) -> T
+where
+    T: Copy
+        + std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<f64, Output = T>,
+{
+    let (t2, t3) = (t * t, t * t * t);
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
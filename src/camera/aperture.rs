@@ -0,0 +1,114 @@
+use std::{f64::consts::TAU, path::Path, sync::Arc};
+
+use image::GrayImage;
+use nalgebra::Point2;
+
+use crate::{
+    distribution::Distribution2D,
+    error::{RayTracingError, Result},
+    sampler::Sampler,
+};
+
+/// Shape of a lens aperture, which sets the shape of out-of-focus highlights (bokeh).
+///
+/// Shapes span the unit disk and are scaled by the lens radius.
+/// Behind the plane in focus the bokeh shows the shape as seen on screen,
+/// in front of it the shape is turned upside down.
+#[derive(Clone)]
+pub enum Aperture {
+    /// A round aperture
+    Disk,
+    /// A regular polygon inscribed in the unit circle, formed by the blades of an iris
+    Polygon {
+        /// Number of blades, at least 3
+        blades: u32,
+        /// Counterclockwise rotation of the polygon on screen in radians,
+        /// at 0 a corner points right
+        rotation: f64,
+    },
+    /// A grayscale mask, sampled proportional to its transmission
+    Mask(Arc<ApertureMask>),
+}
+
+impl Aperture {
+    /// Maps a point in the unit square to a point on the aperture.
+    #[must_use]
+    pub fn sample(&self, p: Point2<f64>) -> Point2<f64> {
+        match self {
+            Self::Disk => Sampler::concentric(p),
+            Self::Polygon { blades, rotation } => {
+                // Pick one of the triangles fanning out from the center with x,
+                // and reuse the remainder of x to sample uniformly inside it
+                let n = f64::from(*blades);
+                let k = (p.x * n).floor().min(n - 1.0);
+                let (u, v) = (p.x.mul_add(n, -k), p.y);
+                let corner = |i: f64| {
+                    let angle = TAU.mul_add(i / n, *rotation);
+                    // Screen y points down along the view plane's v axis
+                    Point2::new(angle.cos(), -angle.sin())
+                };
+                let (b, c) = (corner(k), corner(k + 1.0));
+                (b.coords * (1.0 - v) + c.coords * v).scale(u.sqrt()).into()
+            }
+            Self::Mask(mask) => mask.sample(p),
+        }
+    }
+}
+
+/// An aperture shape loaded from a grayscale image.
+///
+/// The image is fitted into the square around the unit disk, keeping its aspect ratio,
+/// and white pixels transmit light while black pixels block it.
+pub struct ApertureMask {
+    /// Distribution of the pixel values, row by row from the top
+    distribution: Distribution2D,
+    /// Half extent of the image on the aperture
+    extent: Point2<f64>,
+}
+
+impl ApertureMask {
+    /// Loads an aperture mask from an image file, converted to grayscale.
+    ///
+    /// # Errors
+    /// Returns an error if the image cannot be loaded or is black everywhere
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let image = image::open(path).map_err(|e| {
+            RayTracingError::AssetError(format!(
+                "Failed to load aperture mask '{}': {e}",
+                path.display()
+            ))
+        })?;
+        Self::from_image(&image.to_luma8())
+    }
+
+    /// Creates an aperture mask from a grayscale image.
+    ///
+    /// # Errors
+    /// Returns an error if the image is black everywhere
+    pub fn from_image(image: &GrayImage) -> Result<Self> {
+        let values: Vec<f64> = image.pixels().map(|p| f64::from(p.0[0]) / 255.0).collect();
+        if !values.iter().any(|&v| v > 0.0) {
+            return Err(RayTracingError::AssetError(
+                "Aperture mask does not transmit any light".to_string(),
+            ));
+        }
+        let (width, height) = (f64::from(image.width()), f64::from(image.height()));
+        let size = width.max(height);
+        Ok(Self {
+            distribution: Distribution2D::new(&values, image.width() as usize),
+            extent: Point2::new(width / size, height / size),
+        })
+    }
+
+    /// Maps a point in the unit square to a point on the aperture,
+    /// with a density proportional to the transmission of the mask.
+    #[must_use]
+    pub fn sample(&self, p: Point2<f64>) -> Point2<f64> {
+        let (uv, _) = self.distribution.sample(p);
+        // Image columns run right and rows down on screen, like the view plane's u and v
+        Point2::new(
+            2.0f64.mul_add(uv.x, -1.0) * self.extent.x,
+            2.0f64.mul_add(uv.y, -1.0) * self.extent.y,
+        )
+    }
+}
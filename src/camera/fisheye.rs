@@ -0,0 +1,57 @@
+use nalgebra::{Point2, Vector2};
+
+use super::{Camera, Setting};
+use crate::{ray::Ray, sampler::Sampler};
+
+/// Fisheye camera (Chapter 11.1).
+///
+/// The distance of a pixel from the image center maps linearly to the angle
+/// between its ray and the viewing direction. Pixels outside the inscribed
+/// circle generate no rays and stay black.
+pub struct Fisheye {
+    setting: Setting,
+    /// Half of the maximum field of view, in radians
+    psi_max: f64,
+}
+
+impl Fisheye {
+    /// Creates a fisheye camera with the given maximum field of view in degrees
+    #[must_use]
+    pub fn new(setting: Setting, fov: f64) -> Self {
+        Self { setting, psi_max: (fov / 2.0).to_radians() }
+    }
+
+    /// Returns the ray through a view plane point, or `None` outside the image circle
+    #[must_use]
+    fn get_ray(&self, p: Point2<f64>) -> Option<Ray> {
+        let s = &self.setting;
+        // Normalized device coordinates in [-1, 1]
+        let pn = Vector2::new(
+            2.0 / (s.pixel_size * f64::from(s.view_width)) * p.x,
+            2.0 / (s.pixel_size * f64::from(s.view_height)) * p.y,
+        );
+        let r_squared = pn.norm_squared();
+        if r_squared > 1.0 {
+            return None;
+        }
+        let r = r_squared.sqrt();
+        let psi = r * self.psi_max;
+        let (sin_psi, cos_psi) = psi.sin_cos();
+        let (sin_alpha, cos_alpha) = if r > 0.0 { (pn.y / r, pn.x / r) } else { (0.0, 1.0) };
+        let dir = s.u * (sin_psi * cos_alpha) + s.v * (sin_psi * sin_alpha) - s.w * cos_psi;
+        Some(Ray::new(s.eye, dir.normalize()))
+    }
+}
+
+impl Camera for Fisheye {
+    fn setting(&self) -> &Setting {
+        &self.setting
+    }
+
+    fn get_rays(&self, origin: Point2<f64>, sampler: &Sampler) -> Vec<Ray> {
+        sampler
+            .square()
+            .filter_map(|dp| self.get_ray(origin - dp.coords * self.setting.pixel_size))
+            .collect()
+    }
+}
@@ -1,15 +1,49 @@
+mod animation;
+mod aperture;
+mod fisheye;
+mod orthographic;
 mod pinhole;
+mod realistic_lens;
 mod setting;
+mod shutter;
+mod spherical;
+mod stereo;
 mod thin_lens;
 
+pub use animation::*;
+pub use aperture::*;
+pub use fisheye::*;
 use nalgebra::Point2;
+pub use orthographic::*;
 pub use pinhole::*;
+pub use realistic_lens::*;
 pub use setting::*;
+pub use shutter::*;
+pub use spherical::*;
+pub use stereo::*;
 pub use thin_lens::*;
 
-use crate::{ray::Ray, sampler::Sampler};
+use crate::{color::Color, ray::Ray, sampler::Sampler};
 
 pub trait Camera: Send + Sync {
     fn setting(&self) -> &Setting;
+
+    /// Generates the rays for the pixel at `origin` on the view plane.
+    ///
+    /// Cameras may return fewer rays than the sampler has samples,
+    /// the missing samples count as black (e.g. outside a fisheye's image circle).
     fn get_rays(&self, origin: Point2<f64>, sampler: &Sampler) -> Vec<Ray>;
+
+    /// Computes the color of the pixel at `origin` by tracing its rays with `trace`.
+    ///
+    /// The default averages the traced rays over the sampler's sample count.
+    /// Cameras that produce several views per pixel combine them here.
+    fn render_pixel(
+        &self,
+        origin: Point2<f64>,
+        sampler: &Sampler,
+        trace: &dyn Fn(&Ray) -> Color,
+    ) -> Color {
+        self.get_rays(origin, sampler).iter().map(trace).sum::<Color>() / f64::from(sampler.count())
+    }
 }
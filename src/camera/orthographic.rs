@@ -0,0 +1,43 @@
+use nalgebra::Point2;
+
+use super::{Camera, Setting};
+use crate::{ray::Ray, sampler::Sampler};
+
+/// Parallel projection camera (Chapter 9 exercise).
+///
+/// All rays share the viewing direction `-w` and start on the view plane through the eye,
+/// so objects keep their size regardless of distance.
+pub struct Orthographic {
+    setting: Setting,
+    /// Width of the visible area in world units
+    view_width: f64,
+}
+
+impl Orthographic {
+    #[must_use]
+    pub const fn new(setting: Setting, view_width: f64) -> Self {
+        Self { setting, view_width }
+    }
+
+    #[must_use]
+    fn get_ray(&self, p: Point2<f64>) -> Ray {
+        // Convert from view plane units to world units, zooming in with the pixel size
+        let scale = self.view_width / f64::from(self.setting.view_width);
+        let origin =
+            self.setting.eye + self.setting.u * (p.x * scale) + self.setting.v * (p.y * scale);
+        Ray::new(origin, -self.setting.w)
+    }
+}
+
+impl Camera for Orthographic {
+    fn setting(&self) -> &Setting {
+        &self.setting
+    }
+
+    fn get_rays(&self, origin: Point2<f64>, sampler: &Sampler) -> Vec<Ray> {
+        sampler
+            .square()
+            .map(|dp| self.get_ray(origin - dp.coords * self.setting.pixel_size))
+            .collect()
+    }
+}
@@ -28,6 +28,9 @@ impl Camera for Pinhole {
     }
 
     fn get_rays(&self, origin: Point2<f64>, sampler: &Sampler) -> Vec<Ray> {
-        sampler.square().map(|dp| self.get_ray(origin - dp)).collect()
+        sampler
+            .square()
+            .map(|dp| self.get_ray(origin.coords - dp.coords * self.setting.pixel_size))
+            .collect()
     }
 }
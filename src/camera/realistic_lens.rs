@@ -0,0 +1,348 @@
+use std::{fs, path::Path};
+
+use nalgebra::Point2;
+
+use super::{Camera, Setting};
+use crate::{
+    error::{RayTracingError, Result},
+    model::{Pot3, Vec3},
+    ray::Ray,
+    sampler::Sampler,
+};
+
+/// One spherical interface of a lens prescription, in world units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LensElement {
+    /// Radius of curvature, positive when the center lies towards the film,
+    /// zero for the aperture stop
+    pub curvature_radius: f64,
+    /// Distance along the axis to the next interface towards the film
+    pub thickness: f64,
+    /// Index of refraction of the medium towards the film, zero for air
+    pub ior: f64,
+    /// Radius of the clear aperture
+    pub aperture_radius: f64,
+}
+
+impl LensElement {
+    const fn is_stop(&self) -> bool {
+        self.curvature_radius == 0.0
+    }
+
+    /// Index of refraction of the medium behind the interface
+    fn medium(&self) -> f64 {
+        if self.ior == 0.0 { 1.0 } else { self.ior }
+    }
+}
+
+/// A sequence of lens elements, ordered from the scene side to the film side.
+///
+/// Lens space follows pbrt: the film lies at `z = 0` of camera space
+/// and the elements extend towards positive `z`, where the scene is.
+/// The thickness of the last element is the distance to the film.
+#[derive(Debug, Clone)]
+pub struct LensSystem {
+    elements: Vec<LensElement>,
+}
+
+impl LensSystem {
+    /// Loads a lens prescription table.
+    ///
+    /// Each line holds the radius of curvature, thickness, index of refraction
+    /// and aperture diameter of an interface in millimetres. Lines starting
+    /// with `#` are comments.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the prescription file
+    /// * `units_per_mm` - World units per millimetre of the lens
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is malformed
+    pub fn from_file(path: &Path, units_per_mm: f64) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|err| {
+            RayTracingError::AssetError(format!(
+                "Failed to read lens file {}: {err}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&text, units_per_mm)
+    }
+
+    /// Parses a lens prescription table, see [`LensSystem::from_file`].
+    ///
+    /// # Errors
+    /// Returns an error if a line does not hold four numbers or the table is empty
+    pub fn parse(text: &str, units_per_mm: f64) -> Result<Self> {
+        let mut elements = vec![];
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let values = line
+                .split_whitespace()
+                .map(str::parse::<f64>)
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .ok()
+                .filter(|values| values.len() == 4)
+                .ok_or_else(|| {
+                    RayTracingError::AssetError(format!(
+                        "Lens line {}: expected radius, thickness, IOR and aperture",
+                        number + 1
+                    ))
+                })?;
+            elements.push(LensElement {
+                curvature_radius: values[0] * units_per_mm,
+                thickness: values[1] * units_per_mm,
+                ior: values[2],
+                aperture_radius: values[3] * units_per_mm / 2.0,
+            });
+        }
+        if elements.is_empty() {
+            return Err(RayTracingError::AssetError("Lens file has no elements".to_string()));
+        }
+        Ok(Self { elements })
+    }
+
+    /// Returns the interfaces, from the scene side to the film side
+    #[must_use]
+    pub fn elements(&self) -> &[LensElement] {
+        &self.elements
+    }
+
+    /// Distance from the film to the front element
+    fn front_z(&self) -> f64 {
+        self.elements.iter().map(|e| e.thickness).sum()
+    }
+
+    /// Distance from the film to the rear element
+    fn rear_z(&self) -> f64 {
+        self.elements.last().map_or(0.0, |e| e.thickness)
+    }
+
+    fn rear_radius(&self) -> f64 {
+        self.elements.last().map_or(0.0, |e| e.aperture_radius)
+    }
+
+    /// Traces a ray in camera space from the film out of the front element.
+    ///
+    /// Returns `None` when the ray is blocked by an aperture or totally reflected.
+    fn trace_from_film(&self, origin: Pot3, dir: Vec3) -> Option<(Pot3, Vec3)> {
+        let (mut origin, mut dir) = (flip(origin), flip_vector(dir));
+        let mut z = 0.0;
+        for (i, element) in self.elements.iter().enumerate().rev() {
+            z -= element.thickness;
+            let eta_t = if i > 0 { self.elements[i - 1].medium() } else { 1.0 };
+            (origin, dir) = interact(element, z, origin, dir, element.medium() / eta_t)?;
+        }
+        Some((flip(origin), flip_vector(dir)))
+    }
+
+    /// Traces a ray in camera space from the scene out of the rear element.
+    fn trace_from_scene(&self, origin: Pot3, dir: Vec3) -> Option<(Pot3, Vec3)> {
+        let (mut origin, mut dir) = (flip(origin), flip_vector(dir));
+        let mut z = -self.front_z();
+        for (i, element) in self.elements.iter().enumerate() {
+            let eta_i = if i > 0 { self.elements[i - 1].medium() } else { 1.0 };
+            (origin, dir) = interact(element, z, origin, dir, eta_i / element.medium())?;
+            z += element.thickness;
+        }
+        Some((flip(origin), flip_vector(dir)))
+    }
+
+    /// Computes the principal planes and focal points of the thick lens approximation.
+    ///
+    /// Returns `([scene side, film side] principal plane z, [..] focal point z)` in camera space.
+    fn thick_lens(&self) -> Option<([f64; 2], [f64; 2])> {
+        // A paraxial ray just off the axis
+        let x = 0.001 * self.elements[0].aperture_radius;
+
+        let origin = Pot3::new(x, 0.0, self.front_z() + 1.0);
+        let (out_origin, out_dir) = self.trace_from_scene(origin, -Vec3::z())?;
+        let (pz0, fz0) = cardinal_points(x, out_origin, out_dir);
+
+        let origin = Pot3::new(x, 0.0, self.rear_z() - 1.0);
+        let (out_origin, out_dir) = self.trace_from_film(origin, Vec3::z())?;
+        let (pz1, fz1) = cardinal_points(x, out_origin, out_dir);
+
+        Some(([pz0, pz1], [fz0, fz1]))
+    }
+
+    /// Returns the effective focal length of the lens in world units
+    #[must_use]
+    pub fn focal_length(&self) -> Option<f64> {
+        self.thick_lens().map(|(pz, fz)| (fz[0] - pz[0]).abs())
+    }
+
+    /// Moves the film so objects at `distance` from it are in focus.
+    ///
+    /// # Errors
+    /// Returns an error if the lens cannot focus at that distance
+    pub fn focus(&mut self, distance: f64) -> Result<()> {
+        let (pz, fz) = self.thick_lens().ok_or_else(|| {
+            RayTracingError::ConfigError("Paraxial rays do not pass the lens".to_string())
+        })?;
+        let f = fz[0] - pz[0];
+        let z = -distance;
+        let c = (pz[1] - z - pz[0]) * (pz[1] - z - 4.0 * f - pz[0]);
+        if c < 0.0 {
+            return Err(RayTracingError::ConfigError(format!(
+                "The lens cannot focus at distance {distance}"
+            )));
+        }
+        let delta = 0.5 * (pz[1] - z + pz[0] - c.sqrt());
+        if let Some(last) = self.elements.last_mut() {
+            last.thickness += delta;
+        }
+        Ok(())
+    }
+}
+
+/// Flips the z axis between camera space and lens space
+fn flip(p: Pot3) -> Pot3 {
+    Pot3::new(p.x, p.y, -p.z)
+}
+
+fn flip_vector(v: Vec3) -> Vec3 {
+    Vec3::new(v.x, v.y, -v.z)
+}
+
+/// Intersects and refracts a ray in lens space at an interface whose vertex lies at `z`.
+fn interact(
+    element: &LensElement,
+    z: f64,
+    origin: Pot3,
+    dir: Vec3,
+    eta: f64,
+) -> Option<(Pot3, Vec3)> {
+    let (t, normal) = if element.is_stop() {
+        (((z - origin.z) / dir.z), None)
+    } else {
+        let (t, normal) = intersect_spherical(
+            element.curvature_radius,
+            z + element.curvature_radius,
+            origin,
+            dir,
+        )?;
+        (t, Some(normal))
+    };
+    if t < 0.0 {
+        return None;
+    }
+
+    let hit = origin + dir * t;
+    if hit.x.mul_add(hit.x, hit.y * hit.y) > element.aperture_radius * element.aperture_radius {
+        return None;
+    }
+
+    match normal {
+        Some(normal) => Some((hit, refract(&-dir.normalize(), &normal, eta)?)),
+        None => Some((hit, dir)),
+    }
+}
+
+/// Intersects a ray with a spherical interface centered on the axis at `z_center`.
+///
+/// Returns the distance and the normal facing the incoming ray.
+fn intersect_spherical(radius: f64, z_center: f64, origin: Pot3, dir: Vec3) -> Option<(f64, Vec3)> {
+    let oc = origin.coords - Vec3::new(0.0, 0.0, z_center);
+    let a = dir.dot(&dir);
+    let half_b = oc.dot(&dir);
+    let c = radius.mul_add(-radius, oc.dot(&oc));
+    let discriminant = half_b.mul_add(half_b, -a * c);
+    if discriminant < 0.0 {
+        return None;
+    }
+    let sqrt_disc = discriminant.sqrt();
+    let (t0, t1) = ((-half_b - sqrt_disc) / a, (-half_b + sqrt_disc) / a);
+
+    // The interface is the half of the sphere facing the vertex
+    let t = if (dir.z > 0.0) ^ (radius < 0.0) { t0 } else { t1 };
+    if t < 0.0 {
+        return None;
+    }
+    let normal = (oc + dir * t).normalize();
+    Some((t, if normal.dot(&dir) > 0.0 { -normal } else { normal }))
+}
+
+/// Refracts the direction `wi` pointing away from the surface, `eta` being `eta_i / eta_t`.
+///
+/// Returns `None` on total internal reflection.
+fn refract(wi: &Vec3, normal: &Vec3, eta: f64) -> Option<Vec3> {
+    let cos_i = normal.dot(wi);
+    let sin2_i = cos_i.mul_add(-cos_i, 1.0).max(0.0);
+    let sin2_t = eta * eta * sin2_i;
+    if sin2_t >= 1.0 {
+        return None;
+    }
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(-wi * eta + normal * eta.mul_add(cos_i, -cos_t))
+}
+
+/// Finds where a ray leaving the lens crosses the axis and where it meets the incoming height.
+///
+/// Returns the z of the principal plane and of the focal point.
+fn cardinal_points(height: f64, origin: Pot3, dir: Vec3) -> (f64, f64) {
+    let tf = -origin.x / dir.x;
+    let tp = (height - origin.x) / dir.x;
+    (-dir.z.mul_add(tp, origin.z), -dir.z.mul_add(tf, origin.z))
+}
+
+/// Camera tracing rays through a system of spherical lens elements (pbrt, Chapter 6.4).
+///
+/// The eye is the center of the film. The field of view follows from the
+/// focal length of the lens and the film size, so bokeh, vignetting and
+/// distortion come from the lens prescription itself.
+/// Rays blocked inside the lens count as black.
+pub struct RealisticLens {
+    setting: Setting,
+    lens: LensSystem,
+    /// Film width in world units
+    film_width: f64,
+}
+
+impl RealisticLens {
+    /// Creates a realistic lens camera.
+    ///
+    /// # Arguments
+    /// * `setting` - Camera setting, the eye is the center of the film
+    /// * `lens` - Focused lens system
+    /// * `film_diagonal` - Diagonal of the film in world units
+    #[must_use]
+    pub fn new(setting: Setting, lens: LensSystem, film_diagonal: f64) -> Self {
+        let aspect_ratio = f64::from(setting.view_width) / f64::from(setting.view_height);
+        let film_width = film_diagonal * aspect_ratio / aspect_ratio.hypot(1.0);
+        Self { setting, lens, film_width }
+    }
+
+    fn get_ray(&self, p: Point2<f64>, lens_point: Point2<f64>) -> Option<Ray> {
+        // The lens inverts the image, so the film point is mirrored through the axis
+        let scale = self.film_width / f64::from(self.setting.view_width);
+        let film = Pot3::new(-p.x * scale, -p.y * scale, 0.0);
+        let rear = Pot3::new(
+            lens_point.x * self.lens.rear_radius(),
+            lens_point.y * self.lens.rear_radius(),
+            self.lens.rear_z(),
+        );
+
+        let (origin, dir) = self.lens.trace_from_film(film, rear - film)?;
+        let s = &self.setting;
+        Some(Ray::new(
+            s.eye + s.u * origin.x + s.v * origin.y - s.w * origin.z,
+            (s.u * dir.x + s.v * dir.y - s.w * dir.z).normalize(),
+        ))
+    }
+}
+
+impl Camera for RealisticLens {
+    fn setting(&self) -> &Setting {
+        &self.setting
+    }
+
+    fn get_rays(&self, origin: Point2<f64>, sampler: &Sampler) -> Vec<Ray> {
+        sampler
+            .disk()
+            .filter_map(|(sp, dp)| self.get_ray(origin - sp.coords * self.setting.pixel_size, dp))
+            .collect()
+    }
+}
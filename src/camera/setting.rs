@@ -1,11 +1,41 @@
-use crate::model::{Pot3, Vec3};
+use nalgebra::{Rotation3, Unit};
 
+use crate::{
+    config::camera::DEFAULT_FOV,
+    model::{Pot3, Vec3},
+};
+
+/// Field of view of the camera, in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fov {
+    /// Angle between the top and bottom edges of the image
+    Vertical(f64),
+    /// Angle between the left and right edges of the image
+    Horizontal(f64),
+}
+
+/// Position, orientation and framing shared by all cameras (Chapter 9).
+///
+/// The orthonormal basis `u`, `v`, `w` is derived from the eye, look-at point,
+/// up vector and roll. `w` points from the look-at point towards the eye.
+/// The view plane distance follows from the field of view and the image size,
+/// so the framing does not depend on the resolution.
+#[derive(Clone)]
 pub struct Setting {
     pub up: Vec3,
     pub eye: Pot3,
+    pub lookat: Pot3,
     pub u: Vec3,
     pub v: Vec3,
     pub w: Vec3,
+    pub fov: Fov,
+    /// Rotation about the viewing direction, in degrees
+    pub roll: f64,
+    /// Magnification, scales the pixel size by `1 / zoom`
+    pub zoom: f64,
+    /// Width over height of the frame the field of view is fitted to,
+    /// the image's own aspect ratio when `None`
+    pub aspect_ratio: Option<f64>,
     pub view_plane_distance: f64,
     pub view_width: u32,
     pub view_height: u32,
@@ -13,27 +43,135 @@ pub struct Setting {
 }
 
 impl Setting {
+    /// Creates a camera setting looking from `eye` at `lookat`,
+    /// with the y axis up and the default vertical field of view.
     #[must_use]
-    pub fn new(eye: Pot3, lookat: Pot3, view_plane_distance: f64) -> Self {
-        let up = Vec3::new(0.0, 1.0, 0.0);
-        let w = (eye - lookat).normalize();
-        let u = up.cross(&w).normalize();
-        let v = w.cross(&u).normalize();
-        Self {
+    pub fn new(eye: Pot3, lookat: Pot3) -> Self {
+        let mut setting = Self {
+            up: Vec3::y(),
             eye,
-            w,
-            u,
-            v,
-            up,
-            view_plane_distance,
+            lookat,
+            u: Vec3::x(),
+            v: Vec3::y(),
+            w: Vec3::z(),
+            fov: Fov::Vertical(DEFAULT_FOV),
+            roll: 0.0,
+            zoom: 1.0,
+            aspect_ratio: None,
+            view_plane_distance: 1.0,
             view_width: 100,
             view_height: 100,
             pixel_size: 1.0,
-        }
+        };
+        setting.compute_uvw();
+        setting.compute_view_plane();
+        setting
+    }
+
+    /// Sets the up vector.
+    ///
+    /// It only needs to lie in the plane containing the viewing direction
+    /// and the image's vertical axis; it does not have to be perpendicular
+    /// to the viewing direction.
+    #[must_use]
+    pub fn with_up(mut self, up: Vec3) -> Self {
+        self.up = up;
+        self.compute_uvw();
+        self
+    }
+
+    /// Sets the field of view
+    #[must_use]
+    pub fn with_fov(mut self, fov: Fov) -> Self {
+        self.fov = fov;
+        self.compute_view_plane();
+        self
+    }
+
+    /// Sets the rotation about the viewing direction, in degrees
+    #[must_use]
+    pub fn with_roll(mut self, roll: f64) -> Self {
+        self.roll = roll;
+        self.compute_uvw();
+        self
+    }
+
+    /// Sets the magnification, values above 1 narrow the field of view
+    #[must_use]
+    pub fn with_zoom(mut self, zoom: f64) -> Self {
+        self.zoom = zoom;
+        self.compute_view_plane();
+        self
+    }
+
+    /// Sets the aspect ratio the field of view is fitted to.
+    ///
+    /// A horizontal field of view is converted to a vertical one with this ratio,
+    /// so the vertical framing is kept when the image is resized.
+    #[must_use]
+    pub fn with_aspect_ratio(mut self, aspect_ratio: f64) -> Self {
+        self.aspect_ratio = Some(aspect_ratio);
+        self.compute_view_plane();
+        self
+    }
+
+    /// Returns a copy of the setting with the eye moved by `offset`, keeping the orientation
+    #[must_use]
+    pub fn translated(&self, offset: Vec3) -> Self {
+        Self { eye: self.eye + offset, lookat: self.lookat + offset, ..self.clone() }
     }
 
     pub fn set_view(&mut self, (view_width, view_height): (u32, u32)) {
         self.view_width = view_width;
         self.view_height = view_height;
+        self.compute_view_plane();
+    }
+
+    /// Returns the vertical field of view in radians
+    #[must_use]
+    pub fn vertical_fov(&self) -> f64 {
+        match self.fov {
+            Fov::Vertical(fov) => fov.to_radians(),
+            Fov::Horizontal(fov) => {
+                let aspect_ratio = self
+                    .aspect_ratio
+                    .unwrap_or_else(|| f64::from(self.view_width) / f64::from(self.view_height));
+                2.0 * ((fov.to_radians() / 2.0).tan() / aspect_ratio).atan()
+            }
+        }
+    }
+
+    /// Computes the orthonormal basis from the eye, look-at point, up vector and roll.
+    ///
+    /// When the viewing direction is parallel to the up vector (looking straight up
+    /// or down) the world axis least aligned with the viewing direction is used as up.
+    fn compute_uvw(&mut self) {
+        self.w = (self.eye - self.lookat).normalize();
+
+        let mut u = self.up.cross(&self.w);
+        if u.norm_squared() < 1e-12 {
+            let w = self.w.abs();
+            let up = if w.x <= w.y && w.x <= w.z {
+                Vec3::x()
+            } else if w.y <= w.z {
+                Vec3::y()
+            } else {
+                Vec3::z()
+            };
+            u = up.cross(&self.w);
+        }
+        let u = u.normalize();
+        let v = self.w.cross(&u);
+
+        let roll = Rotation3::from_axis_angle(&Unit::new_unchecked(self.w), self.roll.to_radians());
+        self.u = roll * u;
+        self.v = roll * v;
+    }
+
+    /// Computes the pixel size and view plane distance from the zoom and field of view
+    fn compute_view_plane(&mut self) {
+        self.pixel_size = 1.0 / self.zoom;
+        self.view_plane_distance =
+            f64::from(self.view_height) / 2.0 / (self.vertical_fov() / 2.0).tan();
     }
 }
@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+use crate::sampler::Sampler;
+
+/// The interval the camera shutter is open for.
+///
+/// Camera rays are cast at times spread over the interval,
+/// so objects moving in that time are blurred along their path.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Shutter {
+    /// Time the shutter opens
+    pub open: f64,
+    /// Time the shutter closes
+    pub close: f64,
+}
+
+impl Shutter {
+    #[must_use]
+    pub const fn new(open: f64, close: f64) -> Self {
+        Self { open, close }
+    }
+
+    /// Returns one ray time per sample of `sampler`, stratified over the interval
+    #[must_use]
+    pub fn times(&self, sampler: &Sampler) -> Vec<f64> {
+        sampler
+            .times()
+            .into_iter()
+            .map(|t| (self.close - self.open).mul_add(t, self.open))
+            .collect()
+    }
+}
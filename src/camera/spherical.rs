@@ -0,0 +1,64 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use nalgebra::Point2;
+
+use super::{Camera, Setting};
+use crate::{ray::Ray, sampler::Sampler};
+
+/// Spherical panoramic camera (Chapter 11.2).
+///
+/// Maps the image onto longitude and latitude around the eye, producing an
+/// equirectangular projection when the field of view is 360° by 180°.
+pub struct Spherical {
+    setting: Setting,
+    /// Half of the horizontal field of view, in radians
+    lambda_max: f64,
+    /// Half of the vertical field of view, in radians
+    psi_max: f64,
+}
+
+impl Spherical {
+    /// Creates a spherical camera with the given horizontal and vertical field of view in degrees
+    #[must_use]
+    pub fn new(setting: Setting, horizontal_fov: f64, vertical_fov: f64) -> Self {
+        Self {
+            setting,
+            lambda_max: (horizontal_fov / 2.0).to_radians(),
+            psi_max: (vertical_fov / 2.0).to_radians(),
+        }
+    }
+
+    #[must_use]
+    fn get_ray(&self, p: Point2<f64>) -> Ray {
+        let s = &self.setting;
+        // Normalized device coordinates in [-1, 1]
+        let x = 2.0 / (s.pixel_size * f64::from(s.view_width)) * p.x;
+        let y = 2.0 / (s.pixel_size * f64::from(s.view_height)) * p.y;
+
+        // Longitude and latitude of the ray
+        let lambda = x * self.lambda_max;
+        let psi = y * self.psi_max;
+
+        // Polar angles measured from the viewing direction `-w` and the `v` axis
+        let phi = PI - lambda;
+        let theta = FRAC_PI_2 - psi;
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let (sin_theta, cos_theta) = theta.sin_cos();
+
+        let dir = s.u * (sin_theta * sin_phi) + s.v * cos_theta + s.w * (sin_theta * cos_phi);
+        Ray::new(s.eye, dir.normalize())
+    }
+}
+
+impl Camera for Spherical {
+    fn setting(&self) -> &Setting {
+        &self.setting
+    }
+
+    fn get_rays(&self, origin: Point2<f64>, sampler: &Sampler) -> Vec<Ray> {
+        sampler
+            .square()
+            .map(|dp| self.get_ray(origin - dp.coords * self.setting.pixel_size))
+            .collect()
+    }
+}
@@ -0,0 +1,136 @@
+use std::str::FromStr;
+
+use nalgebra::{Point2, Vector2};
+use serde::Serialize;
+
+use super::{Camera, Setting};
+use crate::{color::Color, error::Result, ray::Ray, sampler::Sampler};
+
+/// How the left and right views are arranged in the output image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StereoLayout {
+    /// The two views next to each other, doubling the image width
+    SideBySide,
+    /// Red channel from the left view, green and blue from the right view
+    Anaglyph,
+}
+
+impl FromStr for StereoLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "side-by-side" => Ok(Self::SideBySide),
+            "anaglyph" => Ok(Self::Anaglyph),
+            _ => Err(format!("Unknown stereo layout '{s}', expected side-by-side or anaglyph")),
+        }
+    }
+}
+
+/// How a side-by-side pair is meant to be viewed (Chapter 12.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StereoViewing {
+    /// Left view on the left, for parallel (wall-eyed) viewing
+    Parallel,
+    /// Left view on the right, for transverse (cross-eyed) viewing
+    Transverse,
+}
+
+/// Stereoscopic camera wrapping a camera per eye (Chapter 12).
+///
+/// The eyes are offset along `Setting::u` by half the interocular distance
+/// each, with parallel viewing directions.
+pub struct Stereo {
+    setting: Setting,
+    left: Box<dyn Camera>,
+    right: Box<dyn Camera>,
+    layout: StereoLayout,
+    viewing: StereoViewing,
+}
+
+impl Stereo {
+    /// Creates a stereo camera.
+    ///
+    /// # Arguments
+    /// * `setting` - Camera setting of a single eye, centered between the eyes
+    /// * `interocular_distance` - Distance between the eyes in world units
+    /// * `layout` - How the two views are combined into one image
+    /// * `viewing` - Which side each view is placed on for side-by-side images
+    /// * `make_camera` - Creates the camera for each eye from its setting
+    ///
+    /// # Errors
+    /// Returns the error of `make_camera` if an eye camera cannot be created
+    pub fn new<F>(
+        setting: Setting,
+        interocular_distance: f64,
+        layout: StereoLayout,
+        viewing: StereoViewing,
+        make_camera: F,
+    ) -> Result<Self>
+    where
+        F: Fn(Setting) -> Result<Box<dyn Camera>>,
+    {
+        let offset = setting.u * (interocular_distance / 2.0);
+        let left = make_camera(setting.translated(-offset))?;
+        let right = make_camera(setting.translated(offset))?;
+
+        let mut setting = setting;
+        if layout == StereoLayout::SideBySide {
+            setting.view_width *= 2;
+        }
+
+        Ok(Self { setting, left, right, layout, viewing })
+    }
+
+    /// Returns the eye camera and its view plane point for a point of a side-by-side image
+    fn side_by_side(&self, origin: Point2<f64>) -> (&dyn Camera, Point2<f64>) {
+        let half_width = f64::from(self.setting.view_width / 2) * self.setting.pixel_size;
+        let on_left = origin.x < 0.0;
+        let camera = match (on_left, self.viewing) {
+            (true, StereoViewing::Parallel) | (false, StereoViewing::Transverse) => &self.left,
+            (false, StereoViewing::Parallel) | (true, StereoViewing::Transverse) => &self.right,
+        };
+        // Re-center the point on the half of the image it lies in
+        let shift = if on_left { half_width / 2.0 } else { -half_width / 2.0 };
+        (camera.as_ref(), origin + Vector2::new(shift, 0.0))
+    }
+}
+
+impl Camera for Stereo {
+    fn setting(&self) -> &Setting {
+        &self.setting
+    }
+
+    /// Returns the rays of the eye that is visible at `origin`.
+    /// For anaglyphs these are the left eye's rays.
+    fn get_rays(&self, origin: Point2<f64>, sampler: &Sampler) -> Vec<Ray> {
+        match self.layout {
+            StereoLayout::SideBySide => {
+                let (camera, origin) = self.side_by_side(origin);
+                camera.get_rays(origin, sampler)
+            }
+            StereoLayout::Anaglyph => self.left.get_rays(origin, sampler),
+        }
+    }
+
+    fn render_pixel(
+        &self,
+        origin: Point2<f64>,
+        sampler: &Sampler,
+        trace: &dyn Fn(&Ray) -> Color,
+    ) -> Color {
+        match self.layout {
+            StereoLayout::SideBySide => {
+                let (camera, origin) = self.side_by_side(origin);
+                camera.render_pixel(origin, sampler, trace)
+            }
+            StereoLayout::Anaglyph => {
+                let left = self.left.render_pixel(origin, sampler, trace);
+                let right = self.right.render_pixel(origin, sampler, trace);
+                Color::new(left.x, right.y, right.z)
+            }
+        }
+    }
+}
@@ -1,18 +1,63 @@
-use nalgebra::{Point2, Vector2};
+use nalgebra::Point2;
 
-use super::{Camera, Setting};
+use super::{Aperture, Camera, Setting};
 use crate::{ray::Ray, sampler::Sampler};
 
 pub struct ThinLens {
     setting: Setting,
     lens_radius: f64,
     focal_plane_distance: f64, // f
+    aperture: Aperture,
 }
 
 impl ThinLens {
+    /// Creates a thin lens camera.
+    ///
+    /// The field of view comes from the setting,
+    /// the focal length only determines the aperture together with the f-number.
+    ///
+    /// # Arguments
+    /// * `setting` - Camera setting
+    /// * `focal_length` - Focal length of the lens in world units
+    /// * `f_number` - Focal length divided by the aperture diameter
+    /// * `focus_distance` - Distance from the eye to the plane in focus, along the view direction
     #[must_use]
-    pub const fn new(setting: Setting, lens_radius: f64, focal_plane_distance: f64) -> Self {
-        Self { setting, lens_radius, focal_plane_distance }
+    pub fn new(setting: Setting, focal_length: f64, f_number: f64, focus_distance: f64) -> Self {
+        Self {
+            setting,
+            lens_radius: focal_length / (2.0 * f_number),
+            focal_plane_distance: focus_distance,
+            aperture: Aperture::Disk,
+        }
+    }
+
+    /// Sets the shape of the aperture, a disk by default.
+    #[must_use]
+    pub fn with_aperture(mut self, aperture: Aperture) -> Self {
+        self.aperture = aperture;
+        self
+    }
+
+    /// Finds the focus distance that puts the first hit seen through `origin` in focus.
+    ///
+    /// # Arguments
+    /// * `setting` - Camera setting the focus distance is measured for
+    /// * `origin` - Point on the view plane to focus through
+    /// * `intersects` - Returns the distance to the first hit of a ray, if any
+    ///
+    /// # Returns
+    /// The distance of the hit along the view direction, or `None` if the ray misses
+    #[must_use]
+    pub fn autofocus(
+        setting: &Setting,
+        origin: Point2<f64>,
+        intersects: &dyn Fn(&Ray) -> Option<f64>,
+    ) -> Option<f64> {
+        let dir = (setting.u * origin.x + setting.v * origin.y
+            - setting.w * setting.view_plane_distance)
+            .normalize();
+        // The focal plane is perpendicular to the view direction
+        intersects(&Ray::new(setting.eye, dir)).map(|dist| dist * dir.dot(&-setting.w))
     }
 
     #[must_use]
@@ -34,10 +79,10 @@ impl Camera for ThinLens {
 
     fn get_rays(&self, origin: Point2<f64>, sampler: &Sampler) -> Vec<Ray> {
         sampler
-            .disk()
-            .map(move |(sp, dp)| {
-                let start_point = sp + Vector2::new(origin.x + sp.x, origin.y + sp.y);
-                let end_point = dp * self.lens_radius;
+            .square()
+            .map(move |sp| {
+                let start_point = origin - sp.coords * self.setting.pixel_size;
+                let end_point = self.aperture.sample(sp) * self.lens_radius;
                 self.get_ray(start_point, end_point)
             })
             .collect()
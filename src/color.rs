@@ -24,6 +24,12 @@ pub fn to_rgb(color: &Color) -> Vec<u8> {
     tone_mapping(color).iter().map(|c| ((c * MAX_RGB_VALUE).round() as u8).clamp(0, 255)).collect()
 }
 
+/// Returns the luminance of a linear RGB color with Rec. 709 primaries.
+#[must_use]
+pub fn luminance(color: &Color) -> f64 {
+    0.0722f64.mul_add(color.z, 0.2126f64.mul_add(color.x, 0.7152 * color.y))
+}
+
 /// Applies simple tone mapping to handle HDR colors.
 ///
 /// This uses a basic exposure tone mapping that scales the entire
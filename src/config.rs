@@ -33,14 +33,38 @@ pub mod scene {
 
 /// Camera configuration constants
 pub mod camera {
-    /// Default focal length for cameras
-    pub const DEFAULT_FOCAL_LENGTH: f64 = 520.0;
+    /// Default vertical field of view, in degrees
+    pub const DEFAULT_FOV: f64 = 51.4;
 
-    /// Default thin lens radius (aperture)
-    pub const DEFAULT_LENS_RADIUS: f64 = 0.001;
+    /// Default focal length of the thin lens, in millimetres
+    pub const DEFAULT_FOCAL_LENGTH: f64 = 50.0;
 
-    /// Default focal distance for thin lens
-    pub const DEFAULT_FOCAL_DISTANCE: f64 = 580.0;
+    /// Default f-number of the thin lens
+    pub const DEFAULT_F_NUMBER: f64 = 16.0;
+
+    /// Default lens prescription of the realistic lens camera
+    pub const DEFAULT_LENS_FILE: &str = "./assets/lenses/dgauss.50mm.dat";
+
+    /// Default film diagonal of the realistic lens camera, in millimetres
+    pub const DEFAULT_FILM_DIAGONAL: f64 = 35.0;
+
+    /// Default distance the realistic lens camera focuses at, in world units
+    pub const DEFAULT_LENS_FOCUS_DISTANCE: f64 = 2.6;
+
+    /// Default width of the visible area of the orthographic camera, in world units
+    pub const DEFAULT_ORTHOGRAPHIC_WIDTH: f64 = 2.2;
+
+    /// Default maximum field of view of the fisheye camera, in degrees
+    pub const DEFAULT_FISHEYE_FOV: f64 = 180.0;
+
+    /// Default horizontal field of view of the spherical camera, in degrees
+    pub const DEFAULT_SPHERICAL_HORIZONTAL_FOV: f64 = 360.0;
+
+    /// Default vertical field of view of the spherical camera, in degrees
+    pub const DEFAULT_SPHERICAL_VERTICAL_FOV: f64 = 180.0;
+
+    /// Default distance between the eyes of the stereo camera, in world units
+    pub const DEFAULT_INTEROCULAR_DISTANCE: f64 = 0.1;
 
     /// Default camera eye position
     pub const DEFAULT_EYE_POSITION: [f64; 3] = [0.0, 0.0, -3.0];
@@ -49,6 +73,59 @@ pub mod camera {
     pub const DEFAULT_LOOKAT_POSITION: [f64; 3] = [0.0, 0.0, 0.0];
 }
 
+/// Light configuration constants
+pub mod light {
+    /// Luminous efficacy of 555 nm light, the most lumens a watt can give
+    pub const LUMENS_PER_WATT: f64 = 683.0;
+
+    /// Radiance scaling factor of the directional light
+    pub const DIRECTIONAL_LIGHT_INTENSITY: f64 = 1.0;
+
+    /// Direction towards the directional light, from above the open front of the box
+    pub const DIRECTIONAL_LIGHT_DIRECTION: [f64; 3] = [0.3, -0.5, -1.0];
+
+    /// Position of the point light, in world units below the center of the ceiling
+    pub const POINT_LIGHT_POSITION: [f64; 3] = [0.0, -0.6, 0.0];
+
+    /// Radiant intensity of the spot light along its axis, in watts per steradian
+    pub const SPOT_LIGHT_INTENSITY: f64 = 0.44;
+
+    /// Position of the spot light, in world units below the front of the ceiling
+    pub const SPOT_LIGHT_POSITION: [f64; 3] = [-0.6, -0.9, -0.9];
+
+    /// Point the spot light is aimed at, the center of the large sphere in world units
+    pub const SPOT_LIGHT_TARGET: [f64; 3] = [0.28, 0.78, -0.44];
+
+    /// Half angle of the spot light's cone with full intensity, in degrees
+    pub const SPOT_LIGHT_INNER_ANGLE: f64 = 12.0;
+
+    /// Half angle of the spot light's cone that is lit at all, in degrees
+    pub const SPOT_LIGHT_OUTER_ANGLE: f64 = 20.0;
+
+    /// Radiance per kcd/m² of sky luminance
+    pub const SKY_INTENSITY: f64 = 0.03;
+
+    /// Luminance of the sun above the atmosphere in kcd/m²
+    pub const SUN_LUMINANCE: f64 = 2.0e6;
+
+    /// Apparent diameter of the sun in degrees
+    pub const SUN_ANGULAR_DIAMETER: f64 = 0.53;
+
+    /// Fraction of the sky's light reflected by the ground below the horizon
+    pub const GROUND_ALBEDO: f64 = 0.3;
+
+    /// Size of the environment map the sky is baked into
+    pub const SKY_MAP_WIDTH: u32 = 512;
+    pub const SKY_MAP_HEIGHT: u32 = 256;
+
+    /// Default sun position in degrees, shining in through the open front of the box
+    pub const DEFAULT_SUN_ELEVATION: f64 = 30.0;
+    pub const DEFAULT_SUN_AZIMUTH: f64 = 160.0;
+
+    /// Default turbidity of a clear sky
+    pub const DEFAULT_TURBIDITY: f64 = 3.0;
+}
+
 /// Material configuration constants
 pub mod material {
     /// Default diffuse reflectance for matte materials
@@ -56,6 +133,16 @@ pub mod material {
 
     /// Default ambient reflectance for matte materials
     pub const DEFAULT_AMBIENT_KD: f64 = 0.5;
+
+    /// Diffuse color of meshes without a material
+    pub const DEFAULT_MATERIAL_COLOR: [f64; 3] = [0.8, 0.8, 0.8];
+
+    /// Phong exponent of specular materials that do not give `Ns`
+    pub const DEFAULT_SHININESS: f64 = 10.0;
+
+    /// Sum of the diffuse and specular coefficients of translated Phong materials,
+    /// which must stay below 1
+    pub const MAX_REFLECTANCE: f64 = 0.99;
 }
 
 /// Geometry configuration constants
@@ -73,6 +160,10 @@ pub mod geometry {
 
         /// Large sphere position
         pub const LARGE_SPHERE_POSITION: [f64; 3] = [200.0, 60.0, 400.0];
+
+        /// Distance the small sphere moves between time 0 and time 1, in world units.
+        /// Loading flips the y axis of the scene, so this lifts the sphere off the floor.
+        pub const SMALL_SPHERE_MOTION: [f64; 3] = [0.0, -0.2, 0.0];
     }
 }
 
@@ -93,3 +184,33 @@ pub mod bvh {
     /// Minimum primitives to consider SAH splitting (below this, just create leaf)
     pub const MIN_PRIMITIVES_FOR_SPLIT: usize = 4;
 }
+
+/// Denoiser configuration constants
+pub mod denoise {
+    /// Number of à-trous wavelet passes (the filter footprint doubles each pass)
+    pub const ITERATIONS: u32 = 5;
+
+    /// Edge-stopping sigma for the noisy color, halved after every pass
+    pub const SIGMA_COLOR: f64 = 0.6;
+
+    /// Edge-stopping sigma for the shading normal feature
+    pub const SIGMA_NORMAL: f64 = 0.1;
+
+    /// Edge-stopping sigma for the relative depth difference
+    pub const SIGMA_DEPTH: f64 = 0.05;
+
+    /// Edge-stopping sigma for the albedo feature
+    pub const SIGMA_ALBEDO: f64 = 0.1;
+}
+
+/// Debug tracer configuration constants
+pub mod tracer {
+    /// Barycentric distance to a triangle edge below which the wireframe tracer draws a line
+    pub const WIREFRAME_THICKNESS: f64 = 0.02;
+}
+
+/// Motion blur configuration constants
+pub mod motion {
+    /// Number of times each segment between keyframes is sampled when bounding a motion
+    pub const BOUNDS_STEPS: u32 = 16;
+}
@@ -0,0 +1,128 @@
+//! Piecewise constant distributions for importance sampling.
+//!
+//! Samples are drawn proportional to tabulated non-negative values,
+//! e.g. the transmission of an aperture mask or the brightness of an image,
+//! by inverting the cumulative distribution.
+
+use nalgebra::Point2;
+
+/// A piecewise constant distribution over [0, 1).
+#[derive(Debug, Clone)]
+pub struct Distribution1D {
+    /// Value of each of the equally sized cells
+    func: Vec<f64>,
+    /// Cumulative distribution at the cell boundaries, `func.len() + 1` entries from 0 to 1
+    cdf: Vec<f64>,
+    /// Integral of `func` over [0, 1)
+    integral: f64,
+}
+
+impl Distribution1D {
+    /// Creates a distribution proportional to `func`.
+    ///
+    /// Negative values count as zero. If all values are zero the distribution is uniform.
+    #[must_use]
+    pub fn new(func: Vec<f64>) -> Self {
+        let func: Vec<f64> = func.into_iter().map(|f| f.max(0.0)).collect();
+        #[expect(clippy::cast_precision_loss)]
+        let n = func.len() as f64;
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.0);
+        for f in &func {
+            cdf.push(cdf[cdf.len() - 1] + f / n);
+        }
+        let integral = cdf[cdf.len() - 1];
+        for (i, c) in cdf.iter_mut().enumerate().skip(1) {
+            #[expect(clippy::cast_precision_loss)]
+            let uniform = i as f64 / n;
+            *c = if integral > 0.0 { *c / integral } else { uniform };
+        }
+        Self { func, cdf, integral }
+    }
+
+    /// Returns the integral of the function over [0, 1).
+    #[must_use]
+    pub const fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    /// Returns the number of cells.
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.func.len()
+    }
+
+    /// Maps a uniform `u` in [0, 1) to a sample of the distribution.
+    ///
+    /// # Returns
+    /// The sample in [0, 1), its probability density and the index of its cell
+    #[must_use]
+    pub fn sample(&self, u: f64) -> (f64, f64, usize) {
+        // The last boundary with cdf <= u, skipping empty cells
+        let offset = self.cdf.partition_point(|&c| c <= u).saturating_sub(1).min(self.count() - 1);
+        let width = self.cdf[offset + 1] - self.cdf[offset];
+        let du = if width > 0.0 { (u - self.cdf[offset]) / width } else { 0.0 };
+        #[expect(clippy::cast_precision_loss)]
+        let x = (offset as f64 + du) / self.count() as f64;
+        (x, self.pdf_of(offset), offset)
+    }
+
+    /// Returns the probability density of the cell at `offset`.
+    #[must_use]
+    pub fn pdf_of(&self, offset: usize) -> f64 {
+        if self.integral > 0.0 { self.func[offset] / self.integral } else { 1.0 }
+    }
+}
+
+/// A piecewise constant distribution over [0, 1)², e.g. an image.
+#[derive(Debug, Clone)]
+pub struct Distribution2D {
+    /// Distribution of x within each row
+    conditional: Vec<Distribution1D>,
+    /// Distribution of the rows
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    /// Creates a distribution proportional to `func`, stored row by row with `width` columns.
+    ///
+    /// # Panics
+    /// Panics if `func` is empty or not a whole number of rows
+    #[must_use]
+    pub fn new(func: &[f64], width: usize) -> Self {
+        assert!(width > 0 && !func.is_empty() && func.len().is_multiple_of(width));
+        let conditional: Vec<Distribution1D> =
+            func.chunks(width).map(|row| Distribution1D::new(row.to_vec())).collect();
+        let marginal =
+            Distribution1D::new(conditional.iter().map(Distribution1D::integral).collect());
+        Self { conditional, marginal }
+    }
+
+    /// Maps a uniform point in [0, 1)² to a sample of the distribution.
+    ///
+    /// # Returns
+    /// The sample in [0, 1)² and its probability density
+    #[must_use]
+    pub fn sample(&self, u: Point2<f64>) -> (Point2<f64>, f64) {
+        let (y, pdf_y, row) = self.marginal.sample(u.y);
+        let (x, pdf_x, _) = self.conditional[row].sample(u.x);
+        (Point2::new(x, y), pdf_x * pdf_y)
+    }
+
+    /// Returns the probability density at `p` in [0, 1)².
+    #[must_use]
+    pub fn pdf(&self, p: Point2<f64>) -> f64 {
+        let cell = |v: f64, n: usize| {
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::cast_precision_loss
+            )]
+            let i = (v * n as f64) as usize;
+            i.min(n - 1)
+        };
+        let row = cell(p.y, self.marginal.count());
+        let conditional = &self.conditional[row];
+        conditional.pdf_of(cell(p.x, conditional.count())) * self.marginal.pdf_of(row)
+    }
+}
@@ -13,6 +13,8 @@ pub enum RayTracingError {
     ConfigError(String),
     /// Error occurred during asset loading
     AssetError(String),
+    /// Error occurred while reading or writing a file
+    IoError(std::io::Error),
 }
 
 impl fmt::Display for RayTracingError {
@@ -22,6 +24,7 @@ impl fmt::Display for RayTracingError {
             Self::RenderError(msg) => write!(f, "Render error: {msg}"),
             Self::ConfigError(msg) => write!(f, "Configuration error: {msg}"),
             Self::AssetError(msg) => write!(f, "Asset loading error: {msg}"),
+            Self::IoError(err) => write!(f, "I/O error: {err}"),
         }
     }
 }
@@ -30,6 +33,7 @@ impl std::error::Error for RayTracingError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::ImageError(err) => Some(err),
+            Self::IoError(err) => Some(err),
             Self::RenderError(_) | Self::ConfigError(_) | Self::AssetError(_) => None,
         }
     }
@@ -41,5 +45,11 @@ impl From<image::ImageError> for RayTracingError {
     }
 }
 
+impl From<std::io::Error> for RayTracingError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
 /// Result type alias for raytracing operations.
 pub type Result<T> = std::result::Result<T, RayTracingError>;
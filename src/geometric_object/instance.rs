@@ -0,0 +1,65 @@
+//! Transformed and moving instances of other geometry.
+
+use std::sync::Arc;
+
+use nalgebra::Point3;
+
+use crate::{
+    aabb::Aabb,
+    geometric_object::Geometry,
+    motion::Motion,
+    ray::{HitRecord, Ray},
+};
+
+/// An instance of shared geometry placed by a rigid motion (Chapter 21).
+///
+/// Rays are transformed into the object space of the wrapped geometry
+/// at their time, so a whole mesh BVH can move without being rebuilt.
+pub struct Instance {
+    /// The instanced geometry in object space
+    object: Arc<dyn Geometry>,
+    /// Transform from object space to world space over time
+    motion: Motion,
+    /// World space bounds over the whole motion
+    aabb: Aabb,
+}
+
+impl Instance {
+    /// Creates an instance of `object` moving along `motion`.
+    #[must_use]
+    pub fn new(object: Arc<dyn Geometry>, motion: Motion) -> Self {
+        let aabb = motion.bounds(&object.get_bounding_box());
+        Self { object, motion, aabb }
+    }
+}
+
+impl Geometry for Instance {
+    fn intersects(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let transform = self.motion.at(ray.time);
+        // Rigid transforms preserve distances, so t is the same in both spaces
+        let local = Ray::new(
+            transform.inverse_transform_point(&ray.origin),
+            transform.inverse_transform_vector(&ray.dir),
+        )
+        .with_time(ray.time);
+
+        let mut record = self.object.intersects(&local, t_min, t_max)?;
+        record.hit_point = transform * record.hit_point;
+        record.normal = transform * record.normal;
+        record.dpdu = transform * record.dpdu;
+        record.dpdv = transform * record.dpdv;
+        Some(record)
+    }
+
+    fn get_center(&self) -> Point3<f64> {
+        nalgebra::center(&self.aabb.min, &self.aabb.max)
+    }
+
+    fn get_min_point(&self) -> Point3<f64> {
+        self.aabb.min
+    }
+
+    fn get_max_point(&self) -> Point3<f64> {
+        self.aabb.max
+    }
+}
@@ -1,14 +1,16 @@
 //! Geometric objects and primitives for ray tracing.
 //!
 //! This module provides the core geometric shapes that can be rendered,
-//! including spheres, triangles, and meshes. All geometric objects implement
+//! including spheres, triangles, meshes and moving instances. All geometric objects implement
 //! the `Geometry` trait for ray intersection testing.
 
-use nalgebra::Point3;
+use nalgebra::{Point2, Point3};
 
+mod instance;
 mod sphere;
 mod triangle;
 
+pub use instance::*;
 pub use sphere::*;
 pub use triangle::*;
 
@@ -83,4 +85,17 @@ pub trait Geometry: Send + Sync {
     fn get_samples(&self, _sampler: &Sampler) -> Vec<Point3<f64>> {
         vec![]
     }
+
+    /// Returns the surface area, 0 for geometry that cannot be sampled.
+    fn area(&self) -> f64 {
+        0.0
+    }
+
+    /// Maps a point in the unit square to a point distributed uniformly over the surface.
+    ///
+    /// # Returns
+    /// The point and the surface normal there, or `None` for geometry that cannot be sampled
+    fn sample(&self, _u: Point2<f64>) -> Option<(Point3<f64>, Vec3)> {
+        None
+    }
 }
@@ -1,14 +1,20 @@
 //! Sphere geometric primitive.
 
-use std::ops::{MulAssign, SubAssign};
+use std::{
+    f64::consts::{FRAC_1_PI, PI},
+    ops::{MulAssign, SubAssign},
+};
 
-use nalgebra::Point3;
+use nalgebra::{Isometry3, Point2, Point3};
 
 use crate::{
+    aabb::Aabb,
     geometric_object::Geometry,
     material::Material,
     model::Vec3,
+    motion::Motion,
     ray::{HitRecord, Ray},
+    stats::{self, Counter},
 };
 
 /// A sphere defined by its center and radius.
@@ -22,6 +28,8 @@ pub struct Sphere<M: Material> {
     center: Point3<f64>,
     /// Material properties
     material: M,
+    /// Motion of the sphere over the shutter interval, if moving
+    motion: Option<Motion>,
 }
 
 impl<M: Material> Sphere<M> {
@@ -33,10 +41,66 @@ impl<M: Material> Sphere<M> {
     /// * `center` - The sphere's center position
     /// * `scale` - Scale factor to apply to the sphere
     pub fn new(material: M, radius: f64, center: Point3<f64>, scale: f64) -> Self {
-        let mut sphere = Self { radius, center, material };
+        let mut sphere = Self { radius, center, material, motion: None };
         sphere.scale(scale);
         sphere
     }
+
+    /// Moves the sphere along `motion`, whose transforms apply in world space:
+    /// translations move the center and rotations turn the sphere about the world origin.
+    #[must_use]
+    pub fn with_motion(mut self, motion: Motion) -> Self {
+        self.motion = Some(motion);
+        self
+    }
+
+    /// Returns the transform of the sphere at `time`.
+    fn transform(&self, time: f64) -> Isometry3<f64> {
+        self.motion.as_ref().map_or_else(Isometry3::identity, |motion| motion.at(time))
+    }
+
+    /// Builds the hit record for the intersection at distance `t`.
+    fn hit_record(&self, ray: &Ray, t: f64, transform: &Isometry3<f64>) -> HitRecord<'_> {
+        let hit_point = ray.get_point(t);
+        let normal = ((hit_point - transform * self.center) / self.radius).normalize();
+        // Texture coordinates turn with the sphere
+        let local = transform.inverse_transform_vector(&normal);
+        let uv = Self::uv(&local);
+        let (along_u, along_v) = self.derivatives(&local);
+        HitRecord {
+            dist: t,
+            hit_point,
+            normal,
+            uv,
+            texcoords: uv,
+            dpdu: transform * along_u,
+            dpdv: transform * along_v,
+            material: &self.material,
+        }
+    }
+
+    /// Returns the derivatives of the surface along longitude and latitude
+    /// at the point with the unit normal `normal`, see [`Sphere::uv`].
+    fn derivatives(&self, normal: &Vec3) -> (Vec3, Vec3) {
+        let cos_latitude = normal.x.hypot(normal.z);
+        // The longitude is undefined at the poles
+        let (cos_phi, sin_phi) = if cos_latitude > 0.0 {
+            (normal.x / cos_latitude, normal.z / cos_latitude)
+        } else {
+            (1.0, 0.0)
+        };
+        let along_longitude = Vec3::new(-normal.z, 0.0, normal.x) * (2.0 * PI * self.radius);
+        let along_latitude =
+            Vec3::new(normal.y * cos_phi, -cos_latitude, normal.y * sin_phi) * (PI * self.radius);
+        (along_longitude, along_latitude)
+    }
+
+    /// Maps a unit normal to longitude and latitude, both in [0, 1].
+    fn uv(normal: &Vec3) -> Point2<f64> {
+        let u = 0.5 + normal.z.atan2(normal.x) / (2.0 * PI);
+        let v = 0.5 - normal.y.asin() * FRAC_1_PI;
+        Point2::new(u, v)
+    }
 }
 
 impl<M: Material> Geometry for Sphere<M> {
@@ -44,8 +108,11 @@ impl<M: Material> Geometry for Sphere<M> {
         // Optimized ray-sphere intersection using improved quadratic formula
         // Avoids unnecessary conversions and leverages FMA instructions
 
-        // Vector from ray origin to sphere center
-        let oc = ray.origin - self.center;
+        stats::increment(Counter::PrimitiveTests);
+
+        // Vector from ray origin to sphere center at the time of the ray
+        let transform = self.transform(ray.time);
+        let oc = ray.origin - transform * self.center;
 
         // Coefficients for quadratic equation
         // Using half_b optimization to reduce operations
@@ -75,23 +142,11 @@ impl<M: Material> Geometry for Sphere<M> {
                 return None;
             }
             // Use farther intersection
-            let hit_point = ray.get_point(t_far);
-            return Some(HitRecord {
-                dist: t_far,
-                hit_point,
-                normal: self.normal(&hit_point),
-                material: &self.material,
-            });
+            return Some(self.hit_record(ray, t_far, &transform));
         }
 
         // Use nearer intersection
-        let hit_point = ray.get_point(t);
-        Some(HitRecord {
-            dist: t,
-            hit_point,
-            normal: self.normal(&hit_point),
-            material: &self.material,
-        })
+        Some(self.hit_record(ray, t, &transform))
     }
 
     fn scale(&mut self, l: f64) {
@@ -113,12 +168,23 @@ impl<M: Material> Geometry for Sphere<M> {
     }
 
     fn get_min_point(&self) -> Point3<f64> {
-        // Bounding box minimum: center minus radius in all dimensions
-        self.center - Vec3::repeat(self.radius)
+        self.get_bounding_box().min
     }
 
     fn get_max_point(&self) -> Point3<f64> {
-        // Bounding box maximum: center plus radius in all dimensions
-        self.center + Vec3::repeat(self.radius)
+        self.get_bounding_box().max
+    }
+
+    fn get_bounding_box(&self) -> Aabb {
+        // Center plus and minus the radius in all dimensions
+        let aabb = Aabb::new(
+            self.center - Vec3::repeat(self.radius),
+            self.center + Vec3::repeat(self.radius),
+        );
+        // Moving spheres are bounded over their whole path
+        match &self.motion {
+            Some(motion) => motion.bounds(&aabb),
+            None => aabb,
+        }
     }
 }
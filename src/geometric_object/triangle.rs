@@ -1,4 +1,4 @@
-use nalgebra::{Point3, center};
+use nalgebra::{Point2, Point3, center};
 
 use super::Geometry;
 use crate::{
@@ -6,6 +6,7 @@ use crate::{
     model::Vec3,
     ray::{HitRecord, Ray},
     sampler::Sampler,
+    stats::{self, Counter},
 };
 
 pub struct Triangle<M: Material> {
@@ -13,14 +14,42 @@ pub struct Triangle<M: Material> {
     pub y: Point3<f64>,
     pub z: Point3<f64>,
     material: M,
+    /// Texture coordinates of the vertices, the barycentric coordinates are used without them
+    texcoords: Option<[Point2<f64>; 3]>,
 }
 
 impl<M: Material> Triangle<M> {
     pub fn new(material: M, x: Point3<f64>, y: Point3<f64>, z: Point3<f64>, scale: f64) -> Self {
-        let mut triangle = Self { x, y, z, material };
+        let mut triangle = Self { x, y, z, material, texcoords: None };
         triangle.scale(scale);
         triangle
     }
+
+    /// Sets the texture coordinates of the vertices `x`, `y` and `z`.
+    #[must_use]
+    pub const fn with_texcoords(mut self, texcoords: [Point2<f64>; 3]) -> Self {
+        self.texcoords = Some(texcoords);
+        self
+    }
+
+    /// Returns the texture coordinates at barycentric coordinates `uv`
+    /// and the derivatives of the surface along them.
+    fn texture_frame(&self, uv: Point2<f64>) -> (Point2<f64>, Vec3, Vec3) {
+        let (edge1, edge2) = (self.y - self.x, self.z - self.x);
+        let Some([t0, t1, t2]) = self.texcoords else {
+            return (uv, edge1, edge2);
+        };
+        let texcoords = t0 + (t1 - t0) * uv.x + (t2 - t0) * uv.y;
+        // Solve the edges for the derivatives along the texture axes
+        let (duv1, duv2) = (t1 - t0, t2 - t0);
+        let det = duv1.x.mul_add(duv2.y, -duv1.y * duv2.x);
+        if det.abs() < 1e-12 {
+            return (texcoords, edge1, edge2);
+        }
+        let along_u = (edge1 * duv2.y - edge2 * duv1.y) / det;
+        let along_v = (edge2 * duv1.x - edge1 * duv2.x) / det;
+        (texcoords, along_u, along_v)
+    }
 }
 
 impl<M: Material> Geometry for Triangle<M> {
@@ -30,6 +59,8 @@ impl<M: Material> Geometry for Triangle<M> {
 
         const EPSILON: f64 = 1e-8;
 
+        stats::increment(Counter::PrimitiveTests);
+
         // Calculate edges and determinant vector
         let edge1 = self.y - self.x;
         let edge2 = self.z - self.x;
@@ -70,10 +101,16 @@ impl<M: Material> Geometry for Triangle<M> {
 
         // Valid intersection found
         let hit_point = ray.get_point(t);
+        let uv = Point2::new(u, v);
+        let (texcoords, along_u, along_v) = self.texture_frame(uv);
         Some(HitRecord {
             dist: t,
             hit_point,
             normal: self.normal(&hit_point),
+            uv,
+            texcoords,
+            dpdu: along_u,
+            dpdv: along_v,
             material: &self.material,
         })
     }
@@ -125,4 +162,15 @@ impl<M: Material> Geometry for Triangle<M> {
     fn get_samples(&self, sampler: &Sampler) -> Vec<Point3<f64>> {
         sampler.triangle(&self.x, &self.y, &self.z).collect()
     }
+
+    fn area(&self) -> f64 {
+        (self.y - self.x).cross(&(self.z - self.x)).norm() / 2.0
+    }
+
+    fn sample(&self, u: Point2<f64>) -> Option<(Point3<f64>, Vec3)> {
+        // Fold the upper half of the square back onto the triangle
+        let (a, b) = if u.x + u.y >= 1.0 { (1.0 - u.x, 1.0 - u.y) } else { (u.x, u.y) };
+        let point = self.x + (self.y - self.x) * a + (self.z - self.x) * b;
+        Some((point, self.normal(&point)))
+    }
 }
@@ -4,11 +4,14 @@
 //!
 //! This library provides a complete ray tracing system with:
 //! - Multiple geometric primitives (spheres, triangles)
-//! - Various materials (matte, phong, reflective, emissive)
+//! - Various materials (matte, phong, reflective, dielectric, emissive), textures and bump maps
+//! - OBJ model loading with MTL material translation
 //! - Different lighting models
 //! - Acceleration structures (BVH)
-//! - Multiple camera types (simple, thin lens)
+//! - Multiple camera types (simple, thin lens, orthographic, fisheye, spherical, realistic lens)
 //! - Parallel rendering using Rayon
+//! - Feature-guided denoising
+//! - Motion blur of moving spheres and instances
 //!
 //! ## Example
 //!
@@ -21,6 +24,7 @@
 //!     preview: true,
 //!     camera: raytracing::args::ArgCamera::ThinLens,
 //!     samples: 4,
+//!     ..Args::default()
 //! };
 //! let scene = CornellBox::new(args.width, args.height, &args).unwrap();
 //! let renderer = Renderer::new(Box::new(scene), &args);
@@ -43,6 +47,10 @@ pub mod camera;
 pub mod color;
 /// Configuration constants and settings
 pub mod config;
+/// Feature-guided denoising of rendered images
+pub mod denoiser;
+/// Piecewise constant distributions for importance sampling
+pub mod distribution;
 /// Error types and handling for the raytracing library
 pub mod error;
 /// Geometric objects that can be rendered (spheres, triangles, etc.)
@@ -53,27 +61,83 @@ pub mod light;
 pub mod material;
 /// Mathematical models and vector operations
 pub mod model;
+/// Keyframed rigid motion for motion blur
+pub mod motion;
 /// Ray representation and hit testing
 pub mod ray;
 /// Main rendering engine and ray tracing logic
 pub mod renderer;
+/// Machine-readable JSON render reports
+pub mod report;
 /// Sampling strategies for antialiasing and Monte Carlo integration
 pub mod sampler;
 /// Scene setup and management
 pub mod scene;
+/// Per-thread counters for rays and intersection tests
+pub mod stats;
+/// Image textures for gobos and materials
+pub mod texture;
+/// Debug visualisation tracers
+pub mod tracer;
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        f64::consts::{FRAC_1_PI, FRAC_PI_2, FRAC_PI_4, PI},
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
+    use nalgebra::{Isometry3, Point2};
+
     use crate::{
-        args::{ArgCamera, Args},
+        args::{ArgCamera, ArgTracer, Args, Crop, FrameRange, Pixel, Power},
+        asset::{Asset, AssetOptions},
+        brdf::{Brdf, GlossySpecular, Lambertian},
+        camera::{
+            Aperture, ApertureMask, Camera, CameraKey, CameraPath, Fisheye, Fov, Interpolation,
+            LensSystem, Orthographic, Setting, Spherical, StereoLayout, ThinLens,
+        },
+        color::{Color, luminance},
+        config::{
+            camera::DEFAULT_LENS_FILE,
+            light::{GROUND_ALBEDO, SKY_INTENSITY},
+        },
+        denoiser::Denoiser,
+        distribution::Distribution1D,
+        geometric_object::{Geometry, Instance, Sphere, Triangle},
+        light::{
+            Area, Directional, Environment, IesProfile, Light, LightSampler, LightSelection, Point,
+            Sky, Spot,
+        },
+        material::{Dielectric, Emissive, Matte},
+        model::{Pot3, Vec3},
+        motion::{Keyframe, Motion},
+        ray::{Hit, Ray},
         renderer::Renderer,
-        scene::CornellBox,
+        report::{Report, SCHEMA_VERSION, Timings},
+        sampler::Sampler,
+        scene::{CornellBox, Scene},
+        texture::ImageTexture,
     };
 
+    /// Returns a directory for the files of one test, unique to this test run.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("raytracing_{}_{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
     #[test]
     fn render_basic() {
-        let args =
-            Args { width: 10, height: 10, preview: false, camera: ArgCamera::ThinLens, samples: 4 };
+        let args = Args {
+            width: 10,
+            height: 10,
+            preview: false,
+            camera: ArgCamera::ThinLens,
+            samples: 4,
+            ..Args::default()
+        };
         let scene = CornellBox::new(args.width, args.height, &args).unwrap();
         let renderer = Renderer::new(Box::new(scene), &args);
         let pixels = renderer.render();
@@ -82,8 +146,14 @@ mod tests {
 
     #[test]
     fn render_preview_mode() {
-        let args =
-            Args { width: 5, height: 5, preview: true, camera: ArgCamera::Simple, samples: 1 };
+        let args = Args {
+            width: 5,
+            height: 5,
+            preview: true,
+            camera: ArgCamera::Simple,
+            samples: 1,
+            ..Args::default()
+        };
         let scene = CornellBox::new(args.width, args.height, &args).unwrap();
         let renderer = Renderer::new(Box::new(scene), &args);
         let pixels = renderer.render();
@@ -95,8 +165,16 @@ mod tests {
 
     #[test]
     fn render_different_cameras() {
-        for camera in [ArgCamera::Simple, ArgCamera::ThinLens] {
-            let args = Args { width: 3, height: 3, preview: true, camera, samples: 1 };
+        for camera in [
+            ArgCamera::Simple,
+            ArgCamera::ThinLens,
+            ArgCamera::Orthographic,
+            ArgCamera::Fisheye,
+            ArgCamera::Spherical,
+            ArgCamera::RealisticLens,
+        ] {
+            let args =
+                Args { width: 3, height: 3, preview: true, camera, samples: 1, ..Args::default() };
             let scene = CornellBox::new(args.width, args.height, &args).unwrap();
             let renderer = Renderer::new(Box::new(scene), &args);
             let pixels = renderer.render();
@@ -104,11 +182,86 @@ mod tests {
         }
     }
 
+    #[test]
+    fn orthographic_camera() {
+        let mut setting = Setting::new(Pot3::new(0.0, 0.0, 1.0), Pot3::origin());
+        setting.set_view((8, 8));
+        let ps = setting.pixel_size;
+        let sampler = Sampler::new(1);
+        let ray = |camera: &Orthographic, x: f64, y: f64| {
+            camera.get_rays(Point2::new((x + 0.5) * ps, (y + 0.5) * ps), &sampler).remove(0)
+        };
+
+        // Every ray looks along the viewing direction, from the plane through the eye
+        let camera = Orthographic::new(setting, 3.0);
+        for (x, y) in [(-4.0, -4.0), (0.0, 0.0), (4.0, -2.0), (1.0, 3.0)] {
+            let ray = ray(&camera, x, y);
+            assert!((ray.dir + Vec3::z()).norm() < 1e-12);
+            assert!((ray.origin.z - 1.0).abs() < 1e-12);
+        }
+
+        // The frame spans the configured width, centered on the eye
+        let left = ray(&camera, -4.0, 0.0).origin;
+        let right = ray(&camera, 4.0, 0.0).origin;
+        assert!((right.x - left.x - 3.0).abs() < 1e-12);
+        assert!((right.x + left.x).abs() < 1e-12);
+
+        assert!(Args { orthographic_width: 0.0, ..Args::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn panoramic_cameras() {
+        let mut setting = Setting::new(Pot3::origin(), Pot3::new(0.0, 0.0, -1.0));
+        setting.set_view((8, 8));
+        let ps = setting.pixel_size;
+        // The single sample lies at the pixel center, half a pixel before the view plane point
+        let sampler = Sampler::new(1);
+        let at = |x: f64, y: f64| Point2::new((x + 0.5) * ps, (y + 0.5) * ps);
+        let direction = |camera: &dyn Camera, p: Point2<f64>| camera.get_rays(p, &sampler)[0].dir;
+        let close = |a: Vec3, b: Vec3| (a - b).norm() < 1e-9;
+
+        // The fisheye looks ahead at the center and 45 degrees off axis half way to the rim
+        let fisheye = Fisheye::new(setting.clone(), 180.0);
+        assert!(close(direction(&fisheye, at(0.0, 0.0)), -Vec3::z()));
+        let half = FRAC_PI_4.sin_cos();
+        assert!(close(direction(&fisheye, at(2.0, 0.0)), Vec3::new(half.0, 0.0, -half.1)));
+        assert!(fisheye.get_rays(at(-4.0, -4.0), &sampler).is_empty());
+
+        // The equirectangular image has the view direction at its center and behind at its sides
+        let spherical = Spherical::new(setting, 360.0, 180.0);
+        assert!(close(direction(&spherical, at(0.0, 0.0)), -Vec3::z()));
+        assert!(close(direction(&spherical, at(4.0, 0.0)), Vec3::z()));
+        assert!(close(direction(&spherical, at(2.0, 0.0)), Vec3::x()));
+
+        // Pixels outside the fisheye's image circle stay black
+        let args = Args {
+            width: 8,
+            height: 8,
+            samples: 1,
+            camera: ArgCamera::Fisheye,
+            fisheye_fov: 120.0,
+            ..Args::default()
+        };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let pixels = Renderer::new(Box::new(scene), &args).render();
+        assert_eq!(pixels[0], Color::zeros());
+        assert_ne!(pixels[8 * 4 + 4], Color::zeros());
+
+        assert!(Args { fisheye_fov: 0.0, ..Args::default() }.validate().is_err());
+        assert!(Args { spherical_vertical_fov: 200.0, ..Args::default() }.validate().is_err());
+    }
+
     #[test]
     fn render_different_sample_counts() {
         for samples in [1, 2, 4, 8] {
-            let args =
-                Args { width: 2, height: 2, preview: false, camera: ArgCamera::Simple, samples };
+            let args = Args {
+                width: 2,
+                height: 2,
+                preview: false,
+                camera: ArgCamera::Simple,
+                samples,
+                ..Args::default()
+            };
             let scene = CornellBox::new(args.width, args.height, &args).unwrap();
             let renderer = Renderer::new(Box::new(scene), &args);
             let pixels = renderer.render();
@@ -116,4 +269,1002 @@ mod tests {
             assert_eq!(renderer.sampler.count(), samples);
         }
     }
+
+    #[test]
+    fn render_debug_tracers() {
+        for tracer in [
+            ArgTracer::Normals,
+            ArgTracer::Depth,
+            ArgTracer::Barycentrics,
+            ArgTracer::Wireframe,
+            ArgTracer::MaterialId,
+            ArgTracer::BvhHeatmap,
+        ] {
+            let args = Args { width: 4, height: 4, samples: 1, tracer, ..Args::default() };
+            let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+            let renderer = Renderer::new(Box::new(scene), &args);
+            let pixels = renderer.render();
+            assert_eq!(pixels.len(), 16);
+            assert!(pixels.iter().flatten().all(|c| (0.0..=1.0).contains(c)), "{tracer:?}");
+        }
+    }
+
+    #[test]
+    fn render_collects_stats() {
+        let args = Args { width: 4, height: 4, samples: 4, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        let _ = renderer.render();
+        let stats = renderer.stats();
+        assert_eq!(stats.camera_rays, 4 * 4 * 4);
+        assert!(stats.shadow_rays > 0);
+        assert!(stats.occlusion_rays > 0);
+        assert!(stats.aabb_tests >= stats.bvh_nodes);
+        assert!(stats.primitive_tests > 0);
+        assert!(stats.total_rays() > stats.camera_rays);
+
+        // The denoiser's feature pass is not part of the counted render
+        let _ = renderer.render_features();
+        assert_eq!(renderer.stats(), stats);
+    }
+
+    #[test]
+    fn render_report_schema() {
+        let args = Args { width: 2, height: 2, samples: 1, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        let _ = renderer.render();
+        let report = Report::new(&args, &renderer, Timings::default(), renderer.stats());
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["config"]["camera"], "thin-lens");
+        assert_eq!(json["config"]["sampler"]["samples_per_pixel"], 1);
+        assert_eq!(json["stats"]["camera_rays"], 4);
+        assert!(json["timings"]["bvh_build"].is_number());
+    }
+
+    #[test]
+    fn render_crop_matches_full_render() {
+        // A single sample per pixel is deterministic, so both renders must agree exactly
+        let args = Args { width: 8, height: 8, samples: 1, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let full = Renderer::new(Box::new(scene), &args).render();
+
+        let crop: Crop = "2,1,6,4".parse().unwrap();
+        let args = Args { crop: Some(crop), ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        let cropped = renderer.render();
+        let region = renderer.region();
+        assert_eq!((region.width(), region.height()), (4, 3));
+        assert_eq!(cropped.len(), 12);
+        // The saved image is mirrored horizontally, so the window is too
+        assert_eq!((region.x0, region.x1), (2, 6));
+
+        let expanded = renderer.expand_to_full_frame(&cropped);
+        for (n, color) in (0..).zip(&expanded) {
+            let (x, y) = (n % 8, n / 8);
+            if (region.x0..region.x1).contains(&x) && (region.y0..region.y1).contains(&y) {
+                assert_eq!(color, &full[n as usize]);
+            } else {
+                assert_eq!(color, &Color::zeros());
+            }
+        }
+
+        let normalized: Crop = "0.5,0.0,1.0,0.25".parse().unwrap();
+        assert!(normalized.normalized);
+        let region = normalized.region(8, 8);
+        assert_eq!((region.x0, region.y0, region.x1, region.y1), (0, 0, 4, 2));
+        assert!("1,2,3".parse::<Crop>().is_err());
+        assert!("4,4,2,2".parse::<Crop>().is_err());
+    }
+
+    #[test]
+    fn camera_setting_framing() {
+        let eye = Pot3::new(0.0, 0.0, -3.0);
+        let lookat = Pot3::origin();
+
+        // The framing does not depend on the resolution
+        let mut small = Setting::new(eye, lookat).with_fov(Fov::Vertical(90.0));
+        small.set_view((100, 100));
+        let mut large = small.clone();
+        large.set_view((400, 400));
+        assert!((small.view_plane_distance - 50.0).abs() < 1e-9);
+        assert!((large.view_plane_distance - 200.0).abs() < 1e-9);
+
+        // A horizontal field of view of a square image matches the vertical one
+        let horizontal = small.clone().with_fov(Fov::Horizontal(90.0));
+        assert!((horizontal.view_plane_distance - small.view_plane_distance).abs() < 1e-9);
+        let wide = small.clone().with_fov(Fov::Horizontal(90.0)).with_aspect_ratio(2.0);
+        assert!(wide.view_plane_distance > small.view_plane_distance);
+
+        let zoomed = small.clone().with_zoom(2.0);
+        assert!((zoomed.pixel_size - 0.5).abs() < 1e-9);
+
+        let rolled = small.with_roll(90.0);
+        assert!((rolled.u - Vec3::new(0.0, 1.0, 0.0)).norm() < 1e-9);
+
+        // Looking straight down must still produce an orthonormal basis
+        let down = Setting::new(Pot3::new(0.0, 3.0, 0.0), lookat);
+        assert!(down.u.iter().chain(&down.v).all(|c| c.is_finite()));
+        assert!(down.u.dot(&down.v).abs() < 1e-9);
+        assert!(down.u.dot(&down.w).abs() < 1e-9);
+        assert!((down.u.norm() - 1.0).abs() < 1e-9);
+        let tilted = Setting::new(eye, lookat).with_up(Vec3::new(0.0, 1.0, -1.0));
+        assert!((tilted.v - Vec3::new(0.0, 1.0, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn motion_blur() {
+        let motion = Motion::linear(Vec3::new(2.0, 0.0, 0.0));
+        assert!((motion.at(0.5).translation.vector.x - 1.0).abs() < 1e-9);
+        // The last keyframe holds after the motion ends
+        assert!((motion.at(3.0).translation.vector.x - 2.0).abs() < 1e-9);
+
+        // A sphere of radius 0.5 at (1, 0, 0) turning a quarter turn about the y axis
+        let material = Matte::new(
+            Lambertian::new(0.1, Color::repeat(1.0)),
+            Lambertian::new(0.9, Color::repeat(1.0)),
+        );
+        let sphere: Arc<dyn Geometry> =
+            Arc::new(Sphere::new(material, 0.5, Pot3::new(0.0, 1.0, 1.0), 2.0));
+        let turn = Motion::new(vec![
+            Keyframe { time: 1.0, transform: Isometry3::rotation(Vec3::y() * FRAC_PI_2) },
+            Keyframe { time: 0.0, transform: Isometry3::identity() },
+        ]);
+        let instance = Instance::new(sphere, turn);
+
+        let ray = |time| Ray::new(Pot3::new(0.0, -5.0, -1.0), Vec3::y()).with_time(time);
+        assert!(instance.intersects(&ray(0.0), 0.0, f64::INFINITY).is_none());
+        let hit = instance.intersects(&ray(1.0), 0.0, f64::INFINITY).unwrap();
+        assert!((hit.dist - 4.5).abs() < 1e-9);
+        assert!((hit.normal - Vec3::new(0.0, -1.0, 0.0)).norm() < 1e-9);
+
+        // The bounds cover the whole arc, not just the keyframes
+        let bounds = instance.get_bounding_box();
+        let halfway = FRAC_PI_4.cos() + 0.5;
+        assert!(bounds.max.x >= halfway - 1e-9 && bounds.min.z <= -halfway + 1e-9);
+
+        // The small sphere moves while the shutter is open
+        let args =
+            Args { width: 16, height: 16, samples: 1, tracer: ArgTracer::Depth, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let still = Renderer::new(Box::new(scene), &args).render();
+        let args = Args { shutter_open: 0.0, shutter_close: 1.0, ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        assert_ne!(renderer.render(), still);
+        assert!(Args { shutter_open: 2.0, ..args }.validate().is_err());
+    }
+
+    #[test]
+    fn realistic_lens() {
+        let path = Path::new(DEFAULT_LENS_FILE);
+        let mut lens = LensSystem::from_file(path, 1.0).unwrap();
+        assert_eq!(lens.elements().len(), 11);
+        let focal_length = lens.focal_length().unwrap();
+        assert!((focal_length - 50.0).abs() < 1.0, "focal length {focal_length}");
+
+        // Focusing closer moves the film away from the lens
+        let infinity = lens.elements()[10].thickness;
+        lens.focus(1000.0).unwrap();
+        assert!(lens.elements()[10].thickness > infinity);
+        assert!(lens.focus(50.0).is_err());
+
+        assert!(LensSystem::parse("# only a comment", 1.0).is_err());
+        assert!(LensSystem::parse("29.475 3.76 1.67", 1.0).is_err());
+
+        // The lens vignettes, so the corners receive fewer rays than the center
+        let args =
+            Args { width: 100, height: 100, camera: ArgCamera::RealisticLens, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let sampler = Sampler::new(64);
+        let rays = |x, y| {
+            (0..10).map(|_| scene.camera.get_rays(Point2::new(x, y), &sampler).len()).sum::<usize>()
+        };
+        let (center, corner) = (rays(0.0, 0.0), rays(-50.0, -50.0));
+        assert!(corner < center, "center {center}, corner {corner}");
+
+        // Reframing keeps the lens loaded with the scene instead of reading it again
+        let lens_file = temp_dir("realistic_lens").join("lens.dat");
+        std::fs::copy(path, &lens_file).unwrap();
+        let args = Args { width: 8, height: 8, lens_file: Some(lens_file.clone()), ..args };
+        let mut scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        std::fs::remove_file(&lens_file).unwrap();
+        let key = CameraKey {
+            frame: 1.0,
+            eye: Pot3::new(0.5, 0.0, -3.0),
+            lookat: Pot3::origin(),
+            fov: args.fov,
+        };
+        scene.set_camera_key(&key, &args).unwrap();
+        assert_eq!(scene.camera.setting().eye, key.eye);
+    }
+
+    #[test]
+    fn thin_lens_autofocus() {
+        let mut setting = Setting::new(Pot3::origin(), Pot3::new(0.0, 0.0, -1.0));
+        setting.set_view((100, 100));
+
+        // A wall 2 units in front of the eye is in focus at 2 units through every pixel
+        let wall = |ray: &Ray| Some(-2.0 / ray.dir.z);
+        for origin in [Point2::origin(), Point2::new(30.0, -40.0)] {
+            let distance = ThinLens::autofocus(&setting, origin, &wall).unwrap();
+            assert!((distance - 2.0).abs() < 1e-9, "focus distance {distance}");
+        }
+        assert!(ThinLens::autofocus(&setting, Point2::origin(), &|_| None).is_none());
+
+        assert_eq!("250,400".parse::<Pixel>(), Ok(Pixel { x: 250, y: 400 }));
+        assert!("250".parse::<Pixel>().is_err());
+        let args = Args { width: 16, height: 16, samples: 1, ..Args::default() };
+        assert!(
+            Args { autofocus: Some(Pixel { x: 16, y: 0 }), ..args.clone() }.validate().is_err()
+        );
+        assert!(Args { f_number: 0.0, ..args.clone() }.validate().is_err());
+        assert!(Args { focus_distance: Some(-1.0), ..args.clone() }.validate().is_err());
+
+        // Focusing on a corner of the box renders as well as the default center focus
+        let args = Args { autofocus: Some(Pixel { x: 0, y: 15 }), ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        assert_eq!(Renderer::new(Box::new(scene), &args).render().len(), 256);
+    }
+
+    #[test]
+    fn bokeh_apertures() {
+        let distribution = Distribution1D::new(vec![0.0, 1.0, 3.0]);
+        let (x, pdf, offset) = distribution.sample(0.5);
+        assert_eq!(offset, 2);
+        assert!(x > 2.0 / 3.0 && (pdf - 2.25).abs() < 1e-9);
+
+        let sampler = Sampler::new(64);
+        // A square iris with its corners on the axes
+        let square = Aperture::Polygon { blades: 4, rotation: 0.0 };
+        assert!(sampler.square().all(|p| square.sample(p).coords.abs().sum() <= 1.0 + 1e-9));
+        assert!(sampler.square().all(|p| Aperture::Disk.sample(p).coords.norm() <= 1.0 + 1e-9));
+
+        // Only the top right pixel of a 4x2 mask transmits light
+        let mut image = image::GrayImage::new(4, 2);
+        image.put_pixel(3, 0, image::Luma([255]));
+        let mask = ApertureMask::from_image(&image).unwrap();
+        assert!(
+            sampler
+                .square()
+                .map(|p| mask.sample(p))
+                .all(|p| { (0.5..=1.0).contains(&p.x) && (-0.5..=0.0).contains(&p.y) })
+        );
+        assert!(ApertureMask::from_image(&image::GrayImage::new(4, 2)).is_err());
+
+        let args = Args { width: 8, height: 8, samples: 4, f_number: 2.0, ..Args::default() };
+        assert!(Args { aperture_blades: Some(2), ..args.clone() }.validate().is_err());
+        let path = temp_dir("bokeh_apertures").join("aperture_mask.png");
+        image.save(&path).unwrap();
+        let args = Args { aperture_mask: Some(path), ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        assert_eq!(Renderer::new(Box::new(scene), &args).render().len(), 64);
+    }
+
+    #[test]
+    fn camera_animation() {
+        let text = "# frame eye lookat fov\n\
+                    0 0 0 -3 0 0 0 40\n\
+                    20 2 0 -3 0 0 0 60\n\
+                    10 1 1 -3 0 0 0 50\n";
+        let linear = CameraPath::parse(text, Interpolation::Linear).unwrap();
+        let key = linear.at(5.0);
+        assert!(
+            (key.eye - Pot3::new(0.5, 0.5, -3.0)).norm() < 1e-9 && (key.fov - 45.0).abs() < 1e-9
+        );
+        // The camera holds still outside the keyframes
+        assert_eq!(linear.at(-5.0).eye, Pot3::new(0.0, 0.0, -3.0));
+        assert_eq!(linear.at(30.0).eye, Pot3::new(2.0, 0.0, -3.0));
+
+        // The spline passes through the keyframes but bends between them
+        let spline = CameraPath::parse(text, Interpolation::CatmullRom).unwrap();
+        assert!((spline.at(10.0).eye - Pot3::new(1.0, 1.0, -3.0)).norm() < 1e-9);
+        assert!(spline.at(5.0).eye.y > key.eye.y);
+
+        assert!(CameraPath::parse("", Interpolation::Linear).is_err());
+        assert!(CameraPath::parse("0 0 0 -3 0 0 0", Interpolation::Linear).is_err());
+        assert!("catmull".parse::<Interpolation>().is_err());
+
+        assert_eq!("2..5".parse::<FrameRange>(), Ok(FrameRange { start: 2, end: 5 }));
+        assert!("5..2".parse::<FrameRange>().is_err());
+        let args = Args { width: 8, height: 8, samples: 1, ..Args::default() };
+        let frames = Args { frames: Some(FrameRange { start: 0, end: 3 }), ..args.clone() };
+        assert!(frames.validate().is_err());
+        let frames = Args { output: "frame_%04d.png".to_string(), ..frames };
+        assert!(frames.validate().is_ok());
+        assert_eq!(frames.output_path(7), "frame_0007.png");
+        assert_eq!(Args { output: "%d.png".to_string(), ..args.clone() }.output_path(12), "12.png");
+
+        // Reframing the scene with the default framing renders the same image
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let mut renderer = Renderer::new(Box::new(scene), &args);
+        let still = renderer.render();
+        let mut key = linear.at(0.0);
+        key.fov = args.fov;
+        renderer.scene.set_camera_key(&key, &args).unwrap();
+        assert_eq!(renderer.render(), still);
+        renderer.scene.set_camera_key(&linear.at(20.0), &args).unwrap();
+        assert_ne!(renderer.render(), still);
+    }
+
+    #[test]
+    fn spot_light() {
+        let spot = Spot::new(
+            1.0,
+            Color::repeat(1.0),
+            Pot3::origin(),
+            Pot3::new(0.0, 0.0, 1.0),
+            10.0,
+            20.0,
+        );
+        let along = |degrees: f64| {
+            let angle = degrees.to_radians();
+            spot.falloff(&Vec3::new(angle.sin(), 0.0, angle.cos())).x
+        };
+        assert!((along(5.0) - 1.0).abs() < 1e-9);
+        assert!(along(12.0) > along(15.0) && along(15.0) > along(18.0) && along(18.0) > 0.0);
+        assert!(along(25.0).abs() < f64::EPSILON);
+
+        // Only the top left quarter of the gobo is red, the world's -y axis is up
+        let mut image = image::RgbImage::new(2, 2);
+        image.put_pixel(0, 0, image::Rgb([255, 0, 0]));
+        let spot = Spot::new(
+            1.0,
+            Color::repeat(1.0),
+            Pot3::origin(),
+            Pot3::new(0.0, 0.0, 1.0),
+            30.0,
+            40.0,
+        )
+        .with_gobo(Arc::new(ImageTexture::from_image(&image)));
+        let red = |direction: Vec3| spot.falloff(&direction.normalize()).x;
+        assert!(red(Vec3::new(0.4, -0.4, 1.0)) > 0.9);
+        assert!(red(Vec3::new(-0.4, -0.4, 1.0)) < 0.1);
+        assert!(red(Vec3::new(0.4, 0.4, 1.0)) < 0.1);
+
+        let args = Args { width: 8, height: 8, samples: 1, ..Args::default() };
+        assert!(Args { gobo: Some("gobo.png".into()), ..args.clone() }.validate().is_err());
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let unlit = Renderer::new(Box::new(scene), &args).render();
+        let args = Args { spot_light: true, ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        let lit = renderer.render();
+        assert!(lit.iter().zip(&unlit).all(|(lit, unlit)| lit.sum() >= unlit.sum()));
+        assert_ne!(lit, unlit);
+
+        // Along its axis the spot falls off with distance like a point light of the same intensity
+        let ray = Ray::new(Pot3::new(0.0, 0.0, -9.0), Vec3::z());
+        let material =
+            Matte::new(Lambertian::new(0.0, Color::zeros()), Lambertian::new(0.0, Color::zeros()));
+        let hit = Hit {
+            ray: &ray,
+            hit_point: Pot3::new(0.0, 0.0, -8.0),
+            normal: Vec3::z(),
+            front_face: true,
+            texcoords: Point2::origin(),
+            dpdu: Vec3::x(),
+            dpdv: Vec3::y(),
+            renderer: &renderer,
+            depth: 0,
+            material: &material,
+        };
+        let radiance = |light: &dyn Light| light.sample(&hit)[0].radiance.x;
+        let at = |z: f64| {
+            Spot::new(1.0, Color::repeat(1.0), Pot3::new(0.0, 0.0, z), hit.hit_point, 10.0, 20.0)
+        };
+        let point = Point::new(1.0, Color::repeat(1.0), Pot3::new(0.0, 0.0, -6.0));
+        assert!((radiance(&at(-6.0)) - radiance(&point)).abs() < 1e-12);
+        assert!((radiance(&at(-7.0)) - 4.0 * radiance(&at(-6.0))).abs() < 1e-12);
+        let metres = at(-7.0).with_metres_per_unit(0.5).with_distance_falloff(1.0);
+        assert!((radiance(&metres) - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn point_light_units_and_falloff() {
+        assert_eq!("5W".parse::<Power>(), Ok(Power::Watts(5.0)));
+        assert_eq!("3400lm".parse::<Power>(), Ok(Power::Lumens(3400.0)));
+        assert!("5".parse::<Power>().is_err() && "-1W".parse::<Power>().is_err());
+
+        let watt = Point::from_watts(1.0, Color::repeat(1.0), Pot3::origin());
+        assert!((watt.ls - 1.0 / (4.0 * PI)).abs() < 1e-12);
+        let lumens = Point::from_lumens(683.0, Color::repeat(1.0), Pot3::origin());
+        assert!((lumens.ls - watt.ls).abs() < 1e-12);
+
+        // Everything in the box is closer than a metre, so a steeper falloff brightens it
+        let args = Args {
+            width: 8,
+            height: 8,
+            samples: 1,
+            point_light: Some(Power::Watts(1.0)),
+            ..Args::default()
+        };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let square = Renderer::new(Box::new(scene), &args).render();
+        let args = Args { point_light_falloff: 3.0, ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let cube = Renderer::new(Box::new(scene), &args).render();
+        assert!(cube.iter().zip(&square).all(|(cube, square)| cube.sum() >= square.sum()));
+        assert_ne!(cube, square);
+        assert!(Args { point_light_radius: -1.0, ..args }.validate().is_err());
+    }
+
+    #[test]
+    fn directional_light_shadows() {
+        let sun = Directional::new(1.0, Color::repeat(1.0), Vec3::new(0.0, -2.0, 0.0))
+            .with_angular_diameter(0.53);
+        assert_eq!(sun.direction, Vec3::new(0.0, -1.0, 0.0));
+        assert!((sun.angular_diameter - 0.53f64.to_radians()).abs() < 1e-12);
+
+        // The walls and ceiling keep the sun out of parts of the box
+        let args = Args { width: 16, height: 16, samples: 1, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let unlit = Renderer::new(Box::new(scene), &args).render();
+        let args = Args { directional_light: true, ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let lit = Renderer::new(Box::new(scene), &args).render();
+        let pairs = || lit.iter().zip(&unlit);
+        assert!(pairs().all(|(lit, unlit)| lit.sum() >= unlit.sum()));
+        assert!(pairs().any(|(lit, unlit)| lit.sum() > unlit.sum()));
+        assert!(pairs().filter(|(lit, unlit)| lit == unlit).count() > 16);
+        assert!(Args { directional_light_diameter: 180.0, ..args }.validate().is_err());
+    }
+
+    #[test]
+    fn area_light_sampling() {
+        // A unit square at z = -5 facing a point 3 units in front of it, outside the box
+        let square = |split: bool| -> Vec<Arc<dyn Geometry>> {
+            // Triangles are scaled by 2 / 2 and shifted by -1, with x and y negated
+            let p = |x: f64, y: f64| Pot3::new(1.0 - x, 1.0 - y, -4.0);
+            let m = || {
+                Matte::new(
+                    Lambertian::new(0.0, Color::zeros()),
+                    Lambertian::new(0.0, Color::zeros()),
+                )
+            };
+            let (a, b, c, d) = (p(-0.5, -0.5), p(0.5, -0.5), p(0.5, 0.5), p(-0.5, 0.5));
+            if split {
+                let o = p(0.0, 0.0);
+                [(a, b), (b, c), (c, d), (d, a)]
+                    .into_iter()
+                    .map(|(x, y)| Arc::new(Triangle::new(m(), x, y, o, 2.0)) as Arc<dyn Geometry>)
+                    .collect()
+            } else {
+                vec![
+                    Arc::new(Triangle::new(m(), a, b, c, 2.0)),
+                    Arc::new(Triangle::new(m(), a, c, d, 2.0)),
+                ]
+            }
+        };
+
+        let triangle = &square(false)[0];
+        assert!((triangle.area() - 0.5).abs() < 1e-12);
+        let (point, normal) = triangle.sample(Point2::new(0.9, 0.8)).unwrap();
+        assert!((point.z + 5.0).abs() < 1e-12 && point.x.abs() <= 0.5 && point.y.abs() <= 0.5);
+        assert!((normal.z.abs() - 1.0).abs() < 1e-12);
+
+        let args = Args { width: 4, height: 4, samples: 64, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        let ray = Ray::new(Pot3::new(0.0, 0.0, -9.0), Vec3::z());
+        let material =
+            Matte::new(Lambertian::new(0.0, Color::zeros()), Lambertian::new(0.0, Color::zeros()));
+        let hit = Hit {
+            ray: &ray,
+            hit_point: Pot3::new(0.0, 0.0, -8.0),
+            normal: Vec3::z(),
+            front_face: true,
+            texcoords: Point2::origin(),
+            dpdu: Vec3::x(),
+            dpdv: Vec3::y(),
+            renderer: &renderer,
+            depth: 0,
+            material: &material,
+        };
+        // Irradiance from the samples, which for a small emitter approaches Le A / d²
+        let irradiance = |light: &Area| -> f64 {
+            let samples = light.sample(&hit);
+            samples.iter().map(|s| s.radiance.x * hit.normal.dot(&s.wi)).sum()
+        };
+        let whole = Area::new(square(false), Emissive::new(9.0, Color::repeat(1.0)));
+        let split = Area::new(square(true), Emissive::new(9.0, Color::repeat(1.0)));
+        assert!((whole.area() - 1.0).abs() < 1e-12 && (split.area() - 1.0).abs() < 1e-12);
+        let (e_whole, e_split) = (irradiance(&whole), irradiance(&split));
+        assert!((0.9..1.0).contains(&e_whole), "{e_whole}");
+        assert!((e_whole - e_split).abs() < 0.02, "{e_whole} {e_split}");
+
+        // Nothing arrives at the back of the surface
+        let hit = Hit { normal: -Vec3::z(), ..hit };
+        assert!(whole.sample(&hit).is_empty());
+    }
+
+    #[test]
+    fn multiple_importance_sampling() {
+        let m = || {
+            Matte::new(
+                Lambertian::new(0.0, Color::zeros()),
+                Lambertian::new(1.0, Color::repeat(1.0)),
+            )
+        };
+        // A triangle at z = -5 facing a point at z = -8, outside the box
+        let p = |x: f64, y: f64| Pot3::new(1.0 - x, 1.0 - y, -4.0);
+        let triangle = Triangle::new(m(), p(-1.0, -1.0), p(1.0, -1.0), p(0.0, 1.0), 2.0);
+        let light = Area::new(vec![Arc::new(triangle)], Emissive::new(1.0, Color::repeat(1.0)));
+
+        let args = Args { width: 4, height: 4, samples: 16, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        let ray = Ray::new(Pot3::new(0.0, 0.0, -7.0), -Vec3::z());
+        let material = m();
+        let hit = Hit {
+            ray: &ray,
+            hit_point: Pot3::new(0.0, 0.0, -8.0),
+            normal: Vec3::z(),
+            front_face: true,
+            texcoords: Point2::origin(),
+            dpdu: Vec3::x(),
+            dpdv: Vec3::y(),
+            renderer: &renderer,
+            depth: 0,
+            material: &material,
+        };
+
+        // The light finds the directions it samples with the same density
+        let samples = light.sample(&hit);
+        assert_eq!(samples.len(), 16);
+        for sample in &samples {
+            let pdf = light.pdf(&hit, &sample.wi);
+            assert!((pdf - sample.pdf).abs() < 1e-9 * pdf, "{pdf} {}", sample.pdf);
+        }
+        assert!(light.pdf(&hit, &-Vec3::z()) <= 0.0);
+        let point = Point::new(1.0, Color::repeat(1.0), Pot3::new(0.0, 0.0, -5.0));
+        assert!(point.pdf(&hit, &Vec3::z()) <= 0.0);
+        assert!(light.has_area() && !point.has_area());
+        assert!(point.sample(&hit).iter().all(|s| s.pdf <= 0.0));
+
+        // The brdf densities integrate to one over the hemisphere
+        let integral = |pdf: &dyn Fn(&Vec3) -> f64| {
+            let n = 200;
+            let mut sum = 0.0;
+            for i in 0..n {
+                for j in 0..n {
+                    let u = Point2::new(
+                        (f64::from(i) + 0.5) / f64::from(n),
+                        (f64::from(j) + 0.5) / f64::from(n),
+                    );
+                    // Uniform over the hemisphere with density 1 / 2π
+                    sum += pdf(&Sampler::cosine_power(u, 0.0)) * 2.0 * PI;
+                }
+            }
+            sum / f64::from(n * n)
+        };
+        let diffuse = Lambertian::new(1.0, Color::repeat(1.0));
+        let glossy = GlossySpecular::new(0.5, 20.0, Color::repeat(1.0));
+        assert!((integral(&|wi| diffuse.pdf(&hit, wi)) - 1.0).abs() < 1e-2);
+        assert!((integral(&|wi| glossy.pdf(&hit, wi)) - 1.0).abs() < 2e-2);
+        for u in renderer.sampler.square() {
+            let wi = glossy.sample_wi(&hit, u);
+            assert!(wi.is_none_or(|wi| wi.dot(&hit.normal) > 0.0 && glossy.pdf(&hit, &wi) > 0.0));
+        }
+    }
+
+    #[test]
+    fn environment_light() {
+        // A dim blue sky with one bright texel on the horizon, behind the camera
+        let mut image = image::Rgb32FImage::from_pixel(16, 8, image::Rgb([0.125, 0.25, 0.5]));
+        image.put_pixel(8, 4, image::Rgb([500.0, 400.0, 300.0]));
+        let environment = Environment::from_image(&image).with_intensity(2.0);
+        assert_eq!(environment.radiance_along(&-Vec3::y()), Color::new(0.25, 0.5, 1.0));
+        let phi = 2.0 * PI * 8.5 / 16.0;
+        let bright = Vec3::new(phi.sin(), 0.0, phi.cos());
+        assert_eq!(environment.radiance_along(&bright), Color::new(1000.0, 800.0, 600.0));
+        let rotated = Environment::from_image(&image).with_rotation(90.0);
+        let phi = phi - FRAC_PI_2;
+        let bright = Vec3::new(phi.sin(), 0.0, phi.cos());
+        assert_eq!(rotated.radiance_along(&bright), Color::new(500.0, 400.0, 300.0));
+
+        let path = temp_dir("environment_light").join("environment.hdr");
+        image.save(&path).unwrap();
+        let args = Args {
+            width: 4,
+            height: 4,
+            samples: 16,
+            environment: Some(path),
+            environment_rotation: 90.0,
+            ..Args::default()
+        };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        assert!(scene.environment().is_some());
+        let renderer = Renderer::new(Box::new(scene), &args);
+
+        // Rays leaving the box see the environment
+        let ray = Ray::new(Pot3::new(0.0, 0.0, -3.0), bright);
+        assert_eq!(renderer.trace(&ray, 0), Color::new(500.0, 400.0, 300.0));
+
+        // Light samples favor the bright texel and agree with the density of their directions
+        let material =
+            Matte::new(Lambertian::new(0.0, Color::zeros()), Lambertian::new(0.0, Color::zeros()));
+        let hit = Hit {
+            ray: &ray,
+            hit_point: Pot3::new(0.0, 0.0, -8.0),
+            normal: bright,
+            front_face: true,
+            texcoords: Point2::origin(),
+            dpdu: Vec3::x(),
+            dpdv: Vec3::y(),
+            renderer: &renderer,
+            depth: 0,
+            material: &material,
+        };
+        let environment = renderer.scene.environment().unwrap();
+        let samples = environment.sample(&hit);
+        assert!(samples.iter().filter(|s| s.wi.dot(&bright) > 0.9).count() > 8);
+        for sample in &samples {
+            let pdf = environment.pdf(&hit, &sample.wi);
+            assert!((pdf - sample.pdf).abs() < 1e-6 * pdf, "{pdf} {}", sample.pdf);
+        }
+        assert!(Args { environment_intensity: -1.0, ..Args::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn sky_light() {
+        // With the sun at the zenith the zenith has the model's zenith luminance
+        let sky = Sky::new(90.0, 0.0, 3.0);
+        assert!((sky.sun_direction() + Vec3::y()).norm() < 1e-12);
+        let zenith = luminance(&sky.radiance_along(&-Vec3::y())) / SKY_INTENSITY;
+        let chi = (4.0 / 9.0 - 3.0 / 120.0) * PI;
+        let expected = 4.0453f64.mul_add(3.0, -4.9710).mul_add(chi.tan(), -0.2155 * 3.0) + 2.4192;
+        assert!((zenith - expected).abs() < 0.02 * expected, "{zenith} {expected}");
+
+        // The sky is brightest around the sun, and the ground reflects a part of it
+        let sky = Sky::new(30.0, 160.0, 3.0);
+        let sun = sky.sun_direction();
+        let near = (sun + Vec3::new(0.0, -0.2, 0.0)).normalize();
+        assert!(
+            luminance(&sky.radiance_along(&near)) > 2.0 * luminance(&sky.radiance_along(&-sun))
+        );
+        let below = Vec3::new(0.6, 0.8, 0.0);
+        let above = Vec3::new(0.6, -0.8, 0.0);
+        assert_eq!(sky.radiance_along(&below), sky.radiance_along(&above) * GROUND_ALBEDO);
+
+        // The sun dims and reddens towards the horizon
+        let (high, low) =
+            (Sky::new(60.0, 0.0, 3.0).sun_radiance(), Sky::new(5.0, 0.0, 3.0).sun_radiance());
+        assert!(luminance(&low) < luminance(&high));
+        assert!(low.z / low.x < high.z / high.x);
+
+        // The baked environment holds the sun's power in one bright texel
+        let environment = sky.to_environment(64, 32);
+        assert!(
+            luminance(&environment.radiance_along(&sun))
+                > 100.0 * luminance(&environment.radiance_along(&near))
+        );
+
+        let args = Args { width: 4, height: 4, samples: 1, sky: true, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        assert!(scene.environment().is_some());
+        assert!(Args { turbidity: 11.0, ..args.clone() }.validate().is_err());
+        assert!(Args { sun_elevation: -5.0, ..args.clone() }.validate().is_err());
+        assert!(Args { environment: Some("sky.hdr".into()), ..args }.validate().is_err());
+    }
+
+    #[test]
+    fn light_selection() {
+        let lights: Vec<Arc<dyn Light>> = vec![
+            Arc::new(Point::new(1.0, Color::repeat(1.0), Pot3::new(0.0, 0.0, 0.0))),
+            Arc::new(Point::new(3.0, Color::repeat(1.0), Pot3::new(1.0, 0.0, 0.0))),
+        ];
+        assert!((lights[1].power() - 3.0 * lights[0].power()).abs() < 1e-9);
+
+        // Sampling all lights weights each one fully
+        let all = LightSampler::new(&lights, LightSelection::All, 1);
+        assert_eq!(all.pick().collect::<Vec<_>>(), vec![(0, 1.0), (1, 1.0)]);
+
+        // A single pick by power chooses the brighter light three times as often,
+        // and the weights undo the choice in expectation
+        let power = LightSampler::new(&lights, LightSelection::Power, 1);
+        assert!((power.probability(0) - 0.25).abs() < 1e-12);
+        assert!((power.probability(1) - 0.75).abs() < 1e-12);
+        let picks: Vec<(usize, f64)> = (0..4000).flat_map(|_| power.pick()).collect();
+        #[expect(clippy::cast_precision_loss)]
+        let bright = picks.iter().filter(|(index, _)| *index == 1).count() as f64 / 4000.0;
+        assert!((bright - 0.75).abs() < 0.05, "{bright}");
+        for (index, weight) in &picks {
+            assert!((weight * power.probability(*index) - 1.0).abs() < 1e-12);
+        }
+
+        // Stratified picks by uniform selection take each light once
+        let uniform = LightSampler::new(&lights, LightSelection::Uniform, 2);
+        let mut picks: Vec<(usize, f64)> = uniform.pick().collect();
+        picks.sort_by_key(|(index, _)| *index);
+        assert_eq!(picks, vec![(0, 1.0), (1, 1.0)]);
+        assert!(LightSampler::new(&[], LightSelection::Power, 1).pick().next().is_none());
+
+        assert_eq!("power".parse::<LightSelection>(), Ok(LightSelection::Power));
+        assert!("bvh".parse::<LightSelection>().is_err());
+        let args = Args {
+            width: 4,
+            height: 4,
+            samples: 1,
+            point_light: Some(Power::Watts(1.0)),
+            light_selection: LightSelection::Power,
+            ..Args::default()
+        };
+        assert!(Args { light_samples: 0, ..args.clone() }.validate().is_err());
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let pixels = Renderer::new(Box::new(scene), &args).render();
+        assert!(pixels.iter().all(|c| c.iter().all(|v| v.is_finite() && *v >= 0.0)));
+    }
+
+    #[test]
+    fn ies_profiles() {
+        let load = |name: &str| IesProfile::from_file(&Path::new("assets/ies").join(name)).unwrap();
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        // The downlight is rotationally symmetric and brightest at the nadir
+        let downlight = load("downlight.ies");
+        assert!(close(downlight.peak_candela(), 1200.0));
+        assert!(close(downlight.intensity(0.0, 123.0), 1.0));
+        assert!(close(downlight.intensity(45.0, 0.0), 150.0 / 1200.0));
+        assert!(close(downlight.intensity(2.5, 270.0), (1200.0 + 1172.9) / 2400.0));
+        assert!(downlight.intensity(120.0, 0.0) <= 0.0);
+        assert!(downlight.average() > 0.0 && downlight.average() < 0.5);
+
+        // The wall washer mirrors across the 0-180 degree plane and throws towards 0 degrees
+        let wallwash = load("wallwash.ies");
+        assert!(close(wallwash.intensity(40.0, 30.0), wallwash.intensity(40.0, 330.0)));
+        assert!(wallwash.intensity(40.0, 0.0) > 2.0 * wallwash.intensity(40.0, 180.0));
+
+        // The pendant skips its tilt data and repeats its quadrant around the nadir
+        let pendant = load("pendant.ies");
+        assert!(close(pendant.peak_candela(), 720.0));
+        for h in [135.0, 225.0, 315.0] {
+            assert!(close(pendant.intensity(30.0, 45.0), pendant.intensity(30.0, h)));
+        }
+        assert!(close(pendant.intensity(180.0, 0.0), 420.0 / 720.0));
+
+        // Directions map to photometric angles around the nadir
+        let (nadir, zero) = (Vec3::y(), Vec3::x());
+        assert!(close(
+            wallwash.intensity_along(&nadir, &nadir, &zero),
+            wallwash.intensity(0.0, 0.0)
+        ));
+        let wo = Vec3::new(1.0, 1.0, 0.0).normalize();
+        assert!(close(wallwash.intensity_along(&wo, &nadir, &zero), wallwash.intensity(45.0, 0.0)));
+        let wo = Vec3::new(0.0, 1.0, 1.0).normalize();
+        assert!(close(
+            wallwash.intensity_along(&wo, &nadir, &zero),
+            wallwash.intensity(45.0, 90.0)
+        ));
+
+        // The spot light's cone is shaped by the profile along its axis
+        let target = Pot3::new(0.0, 1.0, 0.0);
+        let spot = || Spot::new(1.0, Color::repeat(1.0), Pot3::origin(), target, 40.0, 45.0);
+        let wo = Vec3::new(0.0, 1.0, 0.5).normalize();
+        let web = downlight.intensity_along(&wo, &nadir, &zero) / downlight.average();
+        let shaped = spot().with_ies(Arc::new(downlight.clone())).falloff(&wo);
+        assert!((shaped - spot().falloff(&wo) * web).norm() < 1e-9);
+        // The shape averages to 1 over the sphere, so a shaped light keeps its power
+        let (mut total, mut weights) = (0.0, 0.0);
+        for i in 0..90 {
+            let theta = PI * (f64::from(i) + 0.5) / 90.0;
+            for j in 0..180 {
+                let phi = 2.0 * PI * (f64::from(j) + 0.5) / 180.0;
+                let wo = Vec3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                total += downlight.shape_along(&wo, &nadir, &zero) * theta.sin();
+                weights += theta.sin();
+            }
+        }
+        assert!((total / weights - 1.0).abs() < 1e-2, "{}", total / weights);
+        let point = Point::new(1.0, Color::repeat(1.0), Pot3::origin());
+        let shaped =
+            Point::new(1.0, Color::repeat(1.0), Pot3::origin()).with_ies(Arc::new(downlight));
+        assert!(close(shaped.power(), point.power()));
+
+        let header = "IESNA:LM-63-2002\nTILT=NONE\n1 1000 1 2 1 1 2 0 0 0\n1 1 10\n";
+        assert!(IesProfile::parse(&format!("{header}0 90\n0\n100 50")).is_ok());
+        assert!(IesProfile::parse("1 1000 1 2 1 1 2 0 0 0\n1 1 10\n0 90\n0\n100 50").is_err());
+        assert!(IesProfile::parse(&format!("{header}0 90\n0\n100")).is_err());
+        assert!(IesProfile::parse(&format!("{header}90 0\n0\n100 50")).is_err());
+        assert!(IesProfile::parse(&format!("{header}0 90\n0\n0 0")).is_err());
+        let single_angle = header.replace("1 2 1 1", "1 1 1 1");
+        assert!(IesProfile::parse(&format!("{single_angle}0\n0\n100")).is_err());
+        let type_b = header.replace("1 1 2 0", "1 2 2 0");
+        assert!(IesProfile::parse(&format!("{type_b}0 90\n0\n100 50")).is_err());
+        assert!(IesProfile::from_file(Path::new("assets/ies/missing.ies")).is_err());
+
+        let args =
+            Args { spot_light_ies: Some("assets/ies/wallwash.ies".into()), ..Args::default() };
+        assert!(args.validate().is_err());
+        let args = Args { width: 4, height: 4, samples: 1, spot_light: true, ..args };
+        assert!(CornellBox::new(args.width, args.height, &args).is_ok());
+    }
+
+    #[test]
+    fn mtl_emission() {
+        let dir = temp_dir("emission");
+        let mtl = "newmtl lamp\nKd 1 1 1\nKe 2 3 4\nemission_strength 5\n\n\
+                   newmtl legacy\nKa 40 40 40\nKd 1 0.5 0.25\n\n\
+                   newmtl wall\nKa 0 0 0\nKd 0.5 0.5 0.5\n";
+        std::fs::write(dir.join("emission.mtl"), mtl).unwrap();
+        let obj = "mtllib emission.mtl\n\
+                   v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+                   o lamp\nusemtl lamp\nf 1 2 3\n\
+                   o legacy\nusemtl legacy\nf 1 2 3\n\
+                   o wall\nusemtl wall\nf 1 2 3\n";
+        let path = dir.join("emission.obj");
+        std::fs::write(&path, obj).unwrap();
+        let path = path.to_str().unwrap();
+
+        // Only the `Ke` material emits, scaled by its strength, from a triangle of area 0.5
+        let asset = Asset::new(path, 2.0).unwrap();
+        assert_eq!(asset.geometries.len(), 3);
+        assert_eq!(asset.lights.len(), 1);
+        let expected = luminance(&Color::new(10.0, 15.0, 20.0)) * 0.5 * PI;
+        assert!((asset.lights[0].power() - expected).abs() < 1e-9 * expected);
+        let ray = Ray::new(Pot3::new(0.7, 0.7, -2.0), Vec3::z());
+        let emitters = asset
+            .geometries
+            .iter()
+            .filter_map(|g| g.intersects(&ray, 0.0, f64::INFINITY))
+            .filter(|record| record.material.emissive())
+            .count();
+        assert_eq!(emitters, 1);
+
+        // The legacy convention turns a large ambient color into an emitter of that strength
+        let options = AssetOptions { legacy_ambient_emission: true, ..AssetOptions::default() };
+        let asset = Asset::with_options(path, 2.0, &options).unwrap();
+        assert_eq!(asset.lights.len(), 2);
+        let expected = luminance(&Color::new(40.0, 20.0, 10.0)) * 0.5 * PI;
+        assert!((asset.lights[1].power() - expected).abs() < 1e-9 * expected);
+    }
+
+    #[test]
+    fn mtl_materials() {
+        let dir = temp_dir("materials");
+        let mut wood = image::RgbImage::new(2, 1);
+        wood.put_pixel(0, 0, image::Rgb([255, 0, 0]));
+        wood.put_pixel(1, 0, image::Rgb([0, 0, 255]));
+        wood.save(dir.join("wood.png")).unwrap();
+        let bump =
+            image::RgbImage::from_fn(8, 8, |x, _| image::Rgb([u8::try_from(32 * x).unwrap(); 3]));
+        bump.save(dir.join("bump.png")).unwrap();
+        let mtl = "newmtl plastic\nKd 0.5 0.5 0.5\nKs 0.4 0.2 0.2\nNs 50\nillum 2\n\n\
+                   newmtl mirror\nKd 0.2 0.2 0.2\nKs 0.8 0.8 0.8\nillum 3\n\n\
+                   newmtl glass\nKd 0 0 0\nKs 1 1 1\nNi 1.5\nTf 0.9 0.8 0.7\nillum 7\n\n\
+                   newmtl chalk\nKd 0.3 0.3 0.3\nKs 0.5 0.5 0.5\nillum 1\n\n\
+                   newmtl wood\nKd 1 1 1\nmap_Kd wood.png\n\
+                   map_bump -bm 2 bump.png\n\n\
+                   newmtl varnish\nKd 1 1 1\nKs 0.5 0.5 0.5\nillum 2\nmap_Kd wood.png\n";
+        std::fs::write(dir.join("materials.mtl"), mtl).unwrap();
+        let obj = "mtllib materials.mtl\n\
+                   v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\n\
+                   o bare\nf 1/1 2/2 3/3\n\
+                   o plastic\nusemtl plastic\nf 1/1 2/2 3/3\n\
+                   o mirror\nusemtl mirror\nf 1/1 2/2 3/3\n\
+                   o glass\nusemtl glass\nf 1/1 2/2 3/3\n\
+                   o chalk\nusemtl chalk\nf 1/1 2/2 3/3\n\
+                   o wood\nusemtl wood\nf 1/1 2/2 3/3\n\
+                   o varnish\nusemtl varnish\nf 1/1 2/2 3/3\n";
+        let path = dir.join("materials.obj");
+        std::fs::write(&path, obj).unwrap();
+        let asset = Asset::new(path.to_str().unwrap(), 2.0).unwrap();
+        assert_eq!(asset.geometries.len(), 7);
+        assert!(asset.lights.is_empty());
+
+        let args = Args { width: 4, height: 4, samples: 1, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        // Model coordinates (x, y) lie at (1 - x, 1 - y, -1) in the world
+        let ray = |x: f64, y: f64| Ray::new(Pot3::new(1.0 - x, 1.0 - y, -5.0), Vec3::z());
+        let record = |index: usize, ray: &Ray| {
+            asset.geometries[index].intersects(ray, 0.0, f64::INFINITY).unwrap()
+        };
+        let center = ray(0.25, 0.1);
+        let albedo = |index: usize| record(index, &center).material.albedo();
+        let close = |a: Color, b: Color| (a - b).norm() < 1e-6;
+        // Default gray, then Phong with ks 0.4 and kd 0.59, mirror, filter and matte colors
+        assert!(close(albedo(0), Color::repeat(0.8)));
+        assert!(close(albedo(1), Color::new(0.695, 0.495, 0.495)));
+        assert!(close(albedo(2), Color::repeat(1.0)));
+        assert!(close(albedo(3), Color::new(0.9, 0.8, 0.7)));
+        assert!(close(albedo(4), Color::repeat(0.3)));
+
+        // Glass reflects 4% at normal incidence and totally from inside at grazing angles
+        assert!((Dielectric::fresnel(1.0, 1.5).0 - 0.04).abs() < 1e-12);
+        assert_eq!(Dielectric::fresnel(0.1, 1.5_f64.recip()).1, None);
+
+        // Texture coordinates run up the image, which shows red on the left and blue on the right
+        assert!((record(5, &center).texcoords - Point2::new(0.25, 0.9)).norm() < 1e-9);
+        let rays = [center, ray(0.75, 0.1)];
+        let hits: Vec<Hit> = [(5, &rays[0]), (5, &rays[1]), (6, &rays[0])]
+            .into_iter()
+            .map(|(index, ray)| {
+                let record = record(index, ray);
+                Hit {
+                    ray,
+                    hit_point: record.hit_point,
+                    normal: record.normal,
+                    front_face: record.normal.dot(&-ray.dir) > 0.0,
+                    texcoords: record.texcoords,
+                    dpdu: record.dpdu,
+                    dpdv: record.dpdv,
+                    renderer: &renderer,
+                    depth: 0,
+                    material: record.material,
+                }
+            })
+            .collect();
+        let (left, right, varnish) = (hits[0], hits[1], hits[2]);
+        let wi = left.normal;
+        assert!(close(left.material.diffuse(&left, &wi), Color::x() * FRAC_1_PI));
+        // The denoiser's albedo guide follows the texture as well
+        assert!(close(left.material.albedo_at(left.texcoords), Color::x()));
+        assert!(close(right.material.albedo_at(right.texcoords), Color::z()));
+        assert!(close(right.material.diffuse(&right, &wi), Color::z() * FRAC_1_PI));
+        // A specular material keeps the texture on its diffuse lobe, with kd 0.49 beside ks 0.5
+        assert!(close(varnish.material.diffuse(&varnish, &wi), Color::x() * 0.49 * FRAC_1_PI));
+
+        // The bump ramp along u tilts the shading normal away from the geometric one
+        let pdf = left.material.pdf(&left, &left.normal);
+        assert!(pdf < 0.99 * FRAC_1_PI, "{pdf}");
+    }
+
+    #[test]
+    fn render_stereo_layouts() {
+        // Without eye separation both views must match a single render
+        let args =
+            Args { width: 4, height: 4, samples: 1, interocular_distance: 0.0, ..Args::default() };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let mono = Renderer::new(Box::new(scene), &args).render();
+
+        let args = Args { stereo: Some(StereoLayout::SideBySide), ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        assert_eq!((scene.view_width(), scene.view_height()), (8, 4));
+        let pair = Renderer::new(Box::new(scene), &args).render();
+        assert_eq!(pair.len(), 32);
+        for (n, color) in mono.iter().enumerate() {
+            let (x, y) = (n % 4, n / 4);
+            assert_eq!(color, &pair[y * 8 + x]);
+            assert_eq!(color, &pair[y * 8 + x + 4]);
+        }
+
+        let args = Args { stereo: Some(StereoLayout::Anaglyph), ..args };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        assert_eq!(Renderer::new(Box::new(scene), &args).render(), mono);
+
+        assert!("over-under".parse::<StereoLayout>().is_err());
+    }
+
+    #[test]
+    fn denoise_cornell_box() {
+        // Sum of absolute differences between horizontally adjacent pixels
+        fn total_variation(pixels: &[Color], width: usize) -> f64 {
+            pixels
+                .chunks(width)
+                .flat_map(|row| row.windows(2).map(|w| (w[0] - w[1]).abs().sum()))
+                .sum()
+        }
+
+        let args = Args {
+            width: 16,
+            height: 16,
+            camera: ArgCamera::Simple,
+            samples: 1,
+            denoise: true,
+            ..Args::default()
+        };
+        let scene = CornellBox::new(args.width, args.height, &args).unwrap();
+        let renderer = Renderer::new(Box::new(scene), &args);
+        let pixels = renderer.render();
+        let features = renderer.render_features();
+        assert_eq!(features.albedo.len(), 256);
+
+        let denoised = Denoiser::default().denoise(&pixels, &features);
+        assert_eq!(denoised.len(), pixels.len());
+        assert!(denoised.iter().all(|c| c.iter().all(|v| v.is_finite())));
+        assert!(total_variation(&denoised, 16) < total_variation(&pixels, 16));
+    }
 }
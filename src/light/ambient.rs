@@ -1,21 +1,32 @@
-use super::Light;
-use crate::{color::Color, model::Vec3, ray::Hit};
+use std::f64::consts::PI;
+
+use super::{Light, LightSample};
+use crate::{
+    color::{Color, luminance},
+    ray::Hit,
+};
 
 pub struct Ambient {
     pub ls: f64,   // radiance scaling factor [0, infinity)
     pub cl: Color, // light color
 }
 
-impl Light for Ambient {
-    fn get_direction(&self, _hit: &Hit) -> Vec3 {
-        Vec3::zeros()
+impl Ambient {
+    /// Returns the radiance arriving from every direction.
+    #[must_use]
+    pub fn radiance(&self) -> Color {
+        self.cl * self.ls
     }
+}
 
-    fn radiance(&self, _hit: &Hit) -> Color {
-        self.cl * self.ls
+impl Light for Ambient {
+    fn power(&self) -> f64 {
+        PI * self.ls * luminance(&self.cl)
     }
 
-    fn shadow_amount(&self, _hit: &Hit) -> f64 {
-        1.0
+    /// Ambient light arrives from every direction at once and is added by the shading
+    /// without samples.
+    fn sample(&self, _hit: &Hit) -> Vec<LightSample> {
+        vec![]
     }
 }
@@ -1,5 +1,12 @@
-use super::{Light, in_shadow};
-use crate::{color::Color, model::Vec3, ray::Hit};
+use std::f64::consts::PI;
+
+use super::{Light, LightSample, occluded, single_sample};
+use crate::{
+    color::{Color, luminance},
+    model::Vec3,
+    ray::Hit,
+    stats::{self, Counter},
+};
 
 pub struct AmbientOcculuder {
     pub ls: f64,
@@ -19,28 +26,42 @@ impl AmbientOcculuder {
         let u = v.cross(&w);
         (u, v, w)
     }
-}
 
-impl Light for AmbientOcculuder {
-    fn get_direction(&self, hit: &Hit) -> Vec3 {
+    /// Returns the direction the occluded ambient light is taken to arrive from.
+    fn get_direction(hit: &Hit) -> Vec3 {
         let (u, v, w) = Self::uvw(hit);
         u + v + w
     }
 
-    fn radiance(&self, _hit: &Hit) -> Color {
-        self.cl * self.ls
-    }
-
-    fn shadow_amount(&self, hit: &Hit) -> f64 {
+    /// Returns the fraction of the hemisphere above the hit point that is not occluded.
+    fn shadow_amount(hit: &Hit) -> f64 {
         let (u, v, w) = Self::uvw(hit);
         let total = hit
             .renderer
             .sampler
             .hemisphere()
             .map(|sp| (u * sp.x + v * sp.y + w * sp.z).normalize())
-            .filter(|dir| !in_shadow(hit, dir, f64::INFINITY))
+            .filter(|dir| {
+                stats::increment(Counter::OcclusionRays);
+                !occluded(hit, dir, f64::INFINITY)
+            })
             .count();
         #[expect(clippy::cast_possible_truncation)]
         (f64::from(total as u32) / f64::from(hit.renderer.sampler.count()))
     }
 }
+
+impl Light for AmbientOcculuder {
+    fn power(&self) -> f64 {
+        PI * self.ls * luminance(&self.cl)
+    }
+
+    fn sample(&self, hit: &Hit) -> Vec<LightSample> {
+        single_sample(
+            hit,
+            Self::get_direction(hit),
+            || self.cl * self.ls,
+            || Self::shadow_amount(hit),
+        )
+    }
+}
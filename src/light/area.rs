@@ -1,50 +1,124 @@
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
-use nalgebra::{Point3, center, distance};
+use nalgebra::{Point2, Point3};
 
-use super::{Light, in_shadow};
-use crate::{color::Color, geometric_object::Geometry, material::Emissive, model::Vec3, ray::Hit};
+use super::{Light, LightHit, LightSample, in_shadow};
+use crate::{
+    accelerator::Bvh,
+    color::luminance,
+    distribution::Distribution1D,
+    geometric_object::Geometry,
+    material::Emissive,
+    model::Vec3,
+    ray::{Hit, Ray},
+};
 
+/// A light emitted from the surface of geometric objects, e.g. the triangles of a ceiling lamp.
+///
+/// The light is sampled uniformly by area, and each sample is weighted by the geometry term
+/// `cos θ_light / r²`, so the illumination falls off with distance and grazing angles
+/// and does not depend on how the emitter is split into objects.
 pub struct Area {
-    center: Point3<f64>,
     geometric_objects: Vec<Arc<dyn Geometry>>,
+    /// Hierarchy over the objects to find the light along a ray, `None` without objects
+    bvh: Option<Arc<dyn Geometry>>,
+    /// Chooses an object proportional to its area
+    distribution: Distribution1D,
+    /// Total area of the objects
+    surface: f64,
     pub material: Emissive,
 }
 
 impl Area {
     #[must_use]
     pub fn new(geometric_objects: Vec<Arc<dyn Geometry>>, material: Emissive) -> Self {
-        let center = geometric_objects
-            .iter()
-            .map(|o| o.get_center())
-            .fold(Point3::origin(), |a, b| center(&a, &b));
-        Self { center, geometric_objects, material }
+        let areas: Vec<f64> = geometric_objects.iter().map(|o| o.area()).collect();
+        let area = areas.iter().sum::<f64>();
+        let bvh =
+            (!geometric_objects.is_empty()).then(|| Bvh::construct(geometric_objects.clone()));
+        Self {
+            geometric_objects,
+            bvh,
+            distribution: Distribution1D::new(areas),
+            surface: area,
+            material,
+        }
+    }
+
+    /// Returns the total emitting area.
+    #[must_use]
+    pub const fn area(&self) -> f64 {
+        self.surface
+    }
+
+    /// Maps a point in the unit square to a point on the light and its normal,
+    /// distributed uniformly by area.
+    fn sample_point(&self, u: Point2<f64>) -> Option<(Point3<f64>, Vec3)> {
+        let (x, _, offset) = self.distribution.sample(u.x);
+        // Stretch the part of u.x within the chosen object's cell back to [0, 1)
+        #[expect(clippy::cast_precision_loss)]
+        let remapped = x
+            .mul_add(self.distribution.count() as f64, -(offset as f64))
+            .clamp(0.0, 1.0 - f64::EPSILON);
+        self.geometric_objects[offset].sample(Point2::new(remapped, u.y))
     }
 }
 
 impl Light for Area {
-    fn get_direction(&self, hit: &Hit) -> Vec3 {
-        (self.center - hit.hit_point).normalize()
+    fn power(&self) -> f64 {
+        // Each point of the surface emits πL into its hemisphere
+        luminance(&self.material.radiance()) * self.surface * PI
     }
 
-    fn radiance(&self, _hit: &Hit) -> Color {
-        self.material.radiance()
+    fn sample(&self, hit: &Hit) -> Vec<LightSample> {
+        if self.surface <= 0.0 {
+            return vec![];
+        }
+        let sampler = &hit.renderer.sampler;
+        let n = f64::from(sampler.count());
+        let le = self.material.radiance();
+        sampler
+            .square()
+            .filter_map(|u| {
+                let (point_on_light, normal) = self.sample_point(u)?;
+                let to_light = point_on_light - hit.hit_point;
+                let d2 = to_light.norm_squared();
+                if d2 <= 0.0 {
+                    return None;
+                }
+                let d = d2.sqrt();
+                let wi = to_light / d;
+                if hit.normal.dot(&wi) <= 0.0 {
+                    return None;
+                }
+                // The emitter is two sided
+                let cos_light = normal.dot(&wi).abs();
+                if cos_light <= 0.0 || in_shadow(hit, &wi, d) {
+                    return None;
+                }
+                // Convert the area density 1 / A to solid angle: pdf = r² / (A cos θ_light)
+                let pdf = d2 / (cos_light * self.surface);
+                Some(LightSample { wi, radiance: le / pdf / n, pdf })
+            })
+            .collect()
     }
 
-    fn shadow_amount(&self, hit: &Hit) -> f64 {
-        let total = self
-            .geometric_objects
-            .iter()
-            .flat_map(|t| t.get_samples(&hit.renderer.sampler))
-            .filter(|point_on_light| {
-                let wi = (point_on_light - hit.hit_point).normalize(); // light direction
-                let d = distance(point_on_light, &hit.hit_point);
-                !in_shadow(hit, &wi, d)
-            })
-            .count();
-        #[expect(clippy::cast_possible_truncation)]
-        (f64::from(total as u32)
-            / f64::from(hit.renderer.sampler.count())
-            / f64::from(self.geometric_objects.len() as u32))
+    fn has_area(&self) -> bool {
+        true
+    }
+
+    fn intersect(&self, hit: &Hit, wi: &Vec3) -> Option<LightHit> {
+        let bvh = self.bvh.as_ref().filter(|_| self.surface > 0.0)?;
+        let ray = Ray::new(hit.hit_point, *wi).with_time(hit.ray.time);
+        let record = bvh.intersects(&ray, 1e-5, f64::INFINITY)?;
+        let cos_light = record.normal.dot(wi).abs();
+        if cos_light <= 0.0 {
+            return None;
+        }
+        Some(LightHit {
+            radiance: self.material.radiance(),
+            distance: record.dist,
+            pdf: record.dist * record.dist / (cos_light * self.surface),
+        })
     }
 }
@@ -1,24 +1,60 @@
-use std::ops::Mul;
+use std::{f64::consts::PI, ops::Mul};
 
-use super::Light;
-use crate::{color::Color, model::Vec3, ray::Hit};
+use super::{Light, LightSample, in_shadow, single_sample, visible_fraction};
+use crate::{
+    color::{Color, luminance},
+    model::Vec3,
+    ray::Hit,
+};
 
+/// A light infinitely far away, like the sun, lighting every point from the same direction.
 pub struct Directional {
     pub ls: f64,
     pub cl: Color,
+    /// Unit vector pointing towards the light
     pub direction: Vec3,
+    /// Apparent diameter of the light in radians, 0 for hard shadows
+    pub angular_diameter: f64,
 }
 
-impl Light for Directional {
-    fn get_direction(&self, _hit: &Hit) -> Vec3 {
-        self.direction
+impl Directional {
+    /// Creates a directional light casting hard shadows.
+    ///
+    /// # Arguments
+    /// * `ls` - Radiance scaling factor
+    /// * `cl` - Color of the light
+    /// * `direction` - Direction towards the light, normalized here
+    #[must_use]
+    pub fn new(ls: f64, cl: Color, direction: Vec3) -> Self {
+        Self { ls, cl, direction: direction.normalize(), angular_diameter: 0.0 }
+    }
+
+    /// Gives the light an apparent diameter in degrees, which softens its shadows.
+    /// The sun is about 0.53 degrees wide.
+    #[must_use]
+    pub fn with_angular_diameter(mut self, degrees: f64) -> Self {
+        self.angular_diameter = degrees.to_radians();
+        self
+    }
+
+    /// Returns the fraction of the light that is not blocked from the hit point.
+    fn shadow_amount(&self, hit: &Hit) -> f64 {
+        if self.angular_diameter <= 0.0 {
+            return f64::from(u32::from(!in_shadow(hit, &self.direction, f64::INFINITY)));
+        }
+
+        // Sample the cone of directions the light covers
+        let radius = (self.angular_diameter / 2.0).tan();
+        visible_fraction(hit, &self.direction, radius, f64::INFINITY)
     }
+}
 
-    fn shadow_amount(&self, _hit: &Hit) -> f64 {
-        1.0
+impl Light for Directional {
+    fn power(&self) -> f64 {
+        PI * self.ls * luminance(&self.cl)
     }
 
-    fn radiance(&self, _hit: &Hit) -> Color {
-        self.cl.mul(self.ls)
+    fn sample(&self, hit: &Hit) -> Vec<LightSample> {
+        single_sample(hit, self.direction, || self.cl.mul(self.ls), || self.shadow_amount(hit))
     }
 }
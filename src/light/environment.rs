@@ -0,0 +1,187 @@
+use std::{
+    f64::consts::{PI, TAU},
+    path::Path,
+};
+
+use image::Rgb32FImage;
+use nalgebra::Point2;
+
+use super::{Light, LightHit, LightSample, in_shadow};
+use crate::{
+    color::{Color, luminance},
+    distribution::Distribution2D,
+    error::{RayTracingError, Result},
+    model::Vec3,
+    ray::Hit,
+};
+
+/// Light arriving from infinitely far away in every direction, from an equirectangular image.
+///
+/// The top row of the image is straight up, which is the world's -y axis,
+/// and the columns wrap around the vertical axis.
+/// Directions are importance sampled by the luminance of the image.
+pub struct Environment {
+    width: u32,
+    height: u32,
+    /// Texels row by row from the top
+    texels: Vec<Color>,
+    /// Radiance scaling factor
+    pub intensity: f64,
+    /// Rotation around the vertical axis in radians
+    pub rotation: f64,
+    /// Chooses texels proportional to the light they contribute
+    distribution: Distribution2D,
+    /// Luminance averaged over all directions
+    average_luminance: f64,
+}
+
+impl Environment {
+    /// Loads an environment from a `.hdr` or `.exr` image, or any other image format.
+    ///
+    /// # Errors
+    /// Returns an error if the image cannot be loaded
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let image = image::open(path).map_err(|e| {
+            RayTracingError::AssetError(format!(
+                "Failed to load environment '{}': {e}",
+                path.display()
+            ))
+        })?;
+        Ok(Self::from_image(&image.to_rgb32f()))
+    }
+
+    /// Creates an environment from a linear RGB image.
+    #[must_use]
+    pub fn from_image(image: &Rgb32FImage) -> Self {
+        let (width, height) = image.dimensions();
+        let texels: Vec<Color> = image
+            .pixels()
+            .map(|p| Color::new(f64::from(p.0[0]), f64::from(p.0[1]), f64::from(p.0[2])))
+            .collect();
+        // Rows near the poles cover a smaller solid angle
+        let func: Vec<f64> = texels
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                #[expect(clippy::cast_possible_truncation)]
+                let row = (i / width as usize) as u32;
+                let theta = PI * (f64::from(row) + 0.5) / f64::from(height);
+                luminance(c) * theta.sin()
+            })
+            .collect();
+        // The sines of all rows sum to about the number of rows times 2/π
+        let weights = f64::from(width) * f64::from(height) * 2.0 / PI;
+        let average_luminance = func.iter().sum::<f64>() / weights;
+        let distribution = Distribution2D::new(&func, width as usize);
+        Self {
+            width,
+            height,
+            texels,
+            intensity: 1.0,
+            rotation: 0.0,
+            distribution,
+            average_luminance,
+        }
+    }
+
+    /// Scales the radiance of the environment.
+    #[must_use]
+    pub const fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Rotates the environment around the vertical axis by `degrees`.
+    #[must_use]
+    pub fn with_rotation(mut self, degrees: f64) -> Self {
+        self.rotation = degrees.to_radians();
+        self
+    }
+
+    /// Returns the radiance arriving from the direction `dir`.
+    #[must_use]
+    pub fn radiance_along(&self, dir: &Vec3) -> Color {
+        self.texel(self.uv(&dir.normalize())) * self.intensity
+    }
+
+    /// Maps a unit direction to image coordinates in [0, 1)².
+    fn uv(&self, dir: &Vec3) -> Point2<f64> {
+        equirectangular_uv(dir, self.rotation)
+    }
+
+    /// Maps image coordinates in [0, 1)² to a unit direction and the sine of its polar angle.
+    fn direction(&self, uv: Point2<f64>) -> (Vec3, f64) {
+        equirectangular_direction(uv, self.rotation)
+    }
+
+    /// Returns the texel covering image coordinates `uv`.
+    fn texel(&self, uv: Point2<f64>) -> Color {
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (x, y) = (
+            ((uv.x * f64::from(self.width)) as u32).min(self.width - 1),
+            ((uv.y * f64::from(self.height)) as u32).min(self.height - 1),
+        );
+        self.texels[(y * self.width + x) as usize]
+    }
+
+    /// Converts a density over the image to a density over solid angle.
+    fn solid_angle_pdf(pdf: f64, sin_theta: f64) -> f64 {
+        if sin_theta <= 0.0 { 0.0 } else { pdf / (2.0 * PI * PI * sin_theta) }
+    }
+}
+
+/// Maps a unit direction to equirectangular image coordinates in [0, 1)²,
+/// with the image rotated around the vertical axis by `rotation` radians.
+pub(super) fn equirectangular_uv(dir: &Vec3, rotation: f64) -> Point2<f64> {
+    let theta = (-dir.y).clamp(-1.0, 1.0).acos();
+    let phi = (dir.x.atan2(dir.z) + rotation).rem_euclid(TAU);
+    Point2::new(phi / TAU, theta / PI)
+}
+
+/// Maps equirectangular image coordinates in [0, 1)² to a unit direction
+/// and the sine of its polar angle, see [`equirectangular_uv`].
+pub(super) fn equirectangular_direction(uv: Point2<f64>, rotation: f64) -> (Vec3, f64) {
+    let theta = uv.y * PI;
+    let phi = uv.x.mul_add(TAU, -rotation);
+    let (sin_theta, cos_theta) = theta.sin_cos();
+    (Vec3::new(sin_theta * phi.sin(), -cos_theta, sin_theta * phi.cos()), sin_theta)
+}
+
+impl Light for Environment {
+    fn power(&self) -> f64 {
+        // A unit disk receives the irradiance πL on an area of π
+        PI * PI * self.average_luminance * self.intensity
+    }
+
+    fn sample(&self, hit: &Hit) -> Vec<LightSample> {
+        let sampler = &hit.renderer.sampler;
+        let n = f64::from(sampler.count());
+        sampler
+            .square()
+            .filter_map(|u| {
+                let (uv, pdf) = self.distribution.sample(u);
+                let (wi, sin_theta) = self.direction(uv);
+                let pdf = Self::solid_angle_pdf(pdf, sin_theta);
+                if pdf <= 0.0 || hit.normal.dot(&wi) <= 0.0 || in_shadow(hit, &wi, f64::INFINITY) {
+                    return None;
+                }
+                let radiance = self.texel(uv) * self.intensity;
+                Some(LightSample { wi, radiance: radiance / pdf / n, pdf })
+            })
+            .collect()
+    }
+
+    fn has_area(&self) -> bool {
+        true
+    }
+
+    fn intersect(&self, _hit: &Hit, wi: &Vec3) -> Option<LightHit> {
+        let uv = self.uv(wi);
+        let sin_theta = (PI * uv.y).sin();
+        Some(LightHit {
+            radiance: self.texel(uv) * self.intensity,
+            distance: f64::INFINITY,
+            pdf: Self::solid_angle_pdf(self.distribution.pdf(uv), sin_theta),
+        })
+    }
+}
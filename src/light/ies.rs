@@ -0,0 +1,250 @@
+use std::{f64::consts::PI, fs, path::Path};
+
+use crate::{
+    error::{RayTracingError, Result},
+    model::Vec3,
+};
+
+/// A photometric web from an IESNA LM-63 file, the measured intensity of a fixture
+/// in every direction.
+///
+/// Only type C photometry is supported, the common one for architectural fixtures:
+/// vertical angles are measured from the nadir, straight down out of the fixture,
+/// and horizontal angles around it. Profiles that cover a half or a quadrant of the
+/// horizontal angles are mirrored to the full circle, and a single horizontal angle
+/// is rotationally symmetric. Tilt data is skipped.
+#[derive(Debug, Clone)]
+pub struct IesProfile {
+    /// Angles from the nadir in degrees, ascending
+    vertical_angles: Vec<f64>,
+    /// Angles around the nadir in degrees, ascending
+    horizontal_angles: Vec<f64>,
+    /// Intensities divided by the peak, all vertical angles of each horizontal angle in turn
+    values: Vec<f64>,
+    /// Peak intensity in candela
+    peak: f64,
+    /// Relative intensity averaged over the sphere
+    average: f64,
+}
+
+impl IesProfile {
+    /// Loads a profile from an `.ies` file.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a valid type C profile
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            RayTracingError::AssetError(format!(
+                "Failed to load IES profile '{}': {e}",
+                path.display()
+            ))
+        })?;
+        Self::parse(&text).map_err(|e| {
+            RayTracingError::AssetError(format!("Invalid IES profile '{}': {e}", path.display()))
+        })
+    }
+
+    /// Parses the text of an LM-63 file.
+    ///
+    /// Keyword lines up to the `TILT=` line are ignored. The numbers after it may be
+    /// separated by whitespace or commas and span lines freely.
+    ///
+    /// # Errors
+    /// Returns an error if the text is malformed, uses another photometric type than C,
+    /// or has no light
+    pub fn parse(text: &str) -> Result<Self> {
+        let error = |message: &str| RayTracingError::AssetError(message.to_string());
+        let mut lines = text.lines().skip_while(|line| !line.trim_start().starts_with("TILT="));
+        let tilt = lines.next().ok_or_else(|| error("Missing TILT line"))?;
+        let numbers = lines
+            .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+            .filter(|token| !token.is_empty())
+            .map(|token| token.parse::<f64>().map_err(|_| error(&format!("Bad number '{token}'"))))
+            .collect::<Result<Vec<f64>>>()?;
+        let mut numbers = numbers.into_iter();
+        let mut take = |count: usize| -> Result<Vec<f64>> {
+            let values: Vec<f64> = numbers.by_ref().take(count).collect();
+            if values.len() < count { Err(error("Unexpected end of data")) } else { Ok(values) }
+        };
+
+        if tilt.trim() == "TILT=INCLUDE" {
+            // Lamp to luminaire geometry, then pairs of angles and multipliers
+            let pairs = count(take(2)?[1])?;
+            take(pairs * 2)?;
+        }
+
+        let header = take(13)?;
+        let (multiplier, vertical_count, horizontal_count) =
+            (header[2], count(header[3])?, count(header[4])?);
+        if count(header[5])? != 1 {
+            return Err(error("Only type C photometry is supported"));
+        }
+        if vertical_count == 0 || horizontal_count == 0 {
+            return Err(error("No angles"));
+        }
+        let vertical_angles = take(vertical_count)?;
+        let horizontal_angles = take(horizontal_count)?;
+        let candela = take(vertical_count * horizontal_count)?;
+
+        let ascending = |angles: &[f64], max: f64| {
+            angles.windows(2).all(|w| w[0] < w[1])
+                && angles.iter().all(|angle| (0.0..=max).contains(angle))
+        };
+        if !ascending(&vertical_angles, 180.0) || !ascending(&horizontal_angles, 360.0) {
+            return Err(error("Angles must ascend within the range of type C photometry"));
+        }
+        let peak = candela.iter().fold(0.0f64, |max, &c| max.max(c * multiplier));
+        if peak <= 0.0 {
+            return Err(error("The profile emits no light"));
+        }
+
+        let mut profile = Self {
+            vertical_angles,
+            horizontal_angles,
+            values: candela.iter().map(|c| (c * multiplier / peak).max(0.0)).collect(),
+            peak,
+            average: 0.0,
+        };
+        profile.average = profile.sphere_average();
+        // A single vertical angle, for instance, lights no solid angle to spread the power over
+        if profile.average <= 0.0 {
+            return Err(error("The profile emits no light over any solid angle"));
+        }
+        Ok(profile)
+    }
+
+    /// Returns the peak intensity in candela.
+    #[must_use]
+    pub const fn peak_candela(&self) -> f64 {
+        self.peak
+    }
+
+    /// Returns the intensity relative to the peak averaged over all directions.
+    #[must_use]
+    pub const fn average(&self) -> f64 {
+        self.average
+    }
+
+    /// Returns the intensity relative to the peak at photometric angles in degrees,
+    /// interpolated bilinearly between the measured angles.
+    ///
+    /// # Arguments
+    /// * `vertical` - Angle from the nadir, between 0 and 180
+    /// * `horizontal` - Angle around the nadir
+    #[must_use]
+    pub fn intensity(&self, vertical: f64, horizontal: f64) -> f64 {
+        let Some((v0, tv)) = locate(&self.vertical_angles, vertical) else {
+            return 0.0;
+        };
+        let Some((h0, th)) = locate(&self.horizontal_angles, self.fold(horizontal)) else {
+            return 0.0;
+        };
+        let n = self.vertical_angles.len();
+        let v1 = (v0 + 1).min(n - 1);
+        let h1 = (h0 + 1).min(self.horizontal_angles.len() - 1);
+        let value = |h: usize, v: usize| self.values[h * n + v];
+        let near = value(h0, v0).mul_add(1.0 - tv, value(h0, v1) * tv);
+        let far = value(h1, v0).mul_add(1.0 - tv, value(h1, v1) * tv);
+        near.mul_add(1.0 - th, far * th)
+    }
+
+    /// Returns the intensity relative to the peak along a unit direction leaving the fixture.
+    ///
+    /// # Arguments
+    /// * `wo` - Unit direction away from the fixture
+    /// * `nadir` - Unit direction of the vertical angle 0
+    /// * `zero` - Unit direction perpendicular to `nadir` of the horizontal angle 0,
+    ///   horizontal angles turn from it towards `nadir × zero`
+    #[must_use]
+    pub fn intensity_along(&self, wo: &Vec3, nadir: &Vec3, zero: &Vec3) -> f64 {
+        let vertical = wo.dot(nadir).clamp(-1.0, 1.0).acos().to_degrees();
+        let horizontal = wo.dot(&nadir.cross(zero)).atan2(wo.dot(zero)).to_degrees();
+        self.intensity(vertical, horizontal)
+    }
+
+    /// Returns the intensity along a unit direction leaving the fixture relative to the
+    /// average over all directions, see [`IesProfile::intensity_along`].
+    ///
+    /// Scaling a light by this factor redistributes its intensity like the fixture
+    /// while keeping its power.
+    #[must_use]
+    pub fn shape_along(&self, wo: &Vec3, nadir: &Vec3, zero: &Vec3) -> f64 {
+        self.intensity_along(wo, nadir, zero) / self.average
+    }
+
+    /// Maps a horizontal angle to the range covered by the measurements,
+    /// mirroring it for symmetric profiles.
+    fn fold(&self, horizontal: f64) -> f64 {
+        let h = horizontal.rem_euclid(360.0);
+        let first = self.horizontal_angles[0];
+        let last = self.horizontal_angles[self.horizontal_angles.len() - 1];
+        if self.horizontal_angles.len() == 1 {
+            // Rotationally symmetric
+            first
+        } else if same(first, 0.0) && same(last, 90.0) {
+            // Symmetric in each quadrant
+            let h = h % 180.0;
+            if h > 90.0 { 180.0 - h } else { h }
+        } else if same(first, 0.0) && same(last, 180.0) {
+            // Symmetric about the 0-180 degree plane
+            if h > 180.0 { 360.0 - h } else { h }
+        } else if same(first, 90.0) && same(last, 270.0) {
+            // Symmetric about the 90-270 degree plane
+            if h < 90.0 {
+                180.0 - h
+            } else if h > 270.0 {
+                540.0 - h
+            } else {
+                h
+            }
+        } else {
+            h.clamp(first, last)
+        }
+    }
+
+    /// Averages the relative intensity over the sphere with the midpoint rule.
+    fn sphere_average(&self) -> f64 {
+        const STEPS: u32 = 90;
+        let mut total = 0.0;
+        let mut weights = 0.0;
+        for i in 0..STEPS {
+            let theta = PI * (f64::from(i) + 0.5) / f64::from(STEPS);
+            for j in 0..2 * STEPS {
+                let phi = 360.0 * (f64::from(j) + 0.5) / f64::from(2 * STEPS);
+                total += self.intensity(theta.to_degrees(), phi) * theta.sin();
+                weights += theta.sin();
+            }
+        }
+        total / weights
+    }
+}
+
+/// Converts a count in an LM-63 file to an integer.
+fn count(value: f64) -> Result<usize> {
+    if value < 0.0 || value.fract() != 0.0 || value > 1e6 {
+        return Err(RayTracingError::AssetError(format!("Bad count {value}")));
+    }
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Ok(value as usize)
+}
+
+/// Finds the measured angle at or below `angle` and how far `angle` lies towards the next one.
+///
+/// # Returns
+/// The index and the fraction in [0, 1], or `None` if `angle` is outside the measured range
+fn locate(angles: &[f64], angle: f64) -> Option<(usize, f64)> {
+    let (first, last) = (angles[0], angles[angles.len() - 1]);
+    if angles.len() == 1 {
+        return same(angle, first).then_some((0, 0.0));
+    }
+    if angle < first || angle > last {
+        return None;
+    }
+    let i = angles.partition_point(|&a| a <= angle).saturating_sub(1).min(angles.len() - 2);
+    Some((i, (angle - angles[i]) / (angles[i + 1] - angles[i])))
+}
+
+/// Returns whether two angles in degrees are equal up to rounding.
+fn same(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
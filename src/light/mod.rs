@@ -2,31 +2,156 @@ mod ambient;
 mod ambient_occuluder;
 mod area;
 mod directional;
+mod environment;
+mod ies;
 mod point;
+mod selection;
+mod sky;
+mod spot;
 
 pub use ambient::*;
 pub use ambient_occuluder::*;
 pub use area::*;
 pub use directional::*;
+pub use environment::*;
+pub use ies::*;
 pub use point::*;
+pub use selection::*;
+pub use sky::*;
+pub use spot::*;
 
 use crate::{
     color::Color,
     model::Vec3,
     ray::{Hit, Ray},
+    stats::{self, Counter},
 };
 
+/// Light arriving at a hit point from one sampled direction.
+#[derive(Debug, Clone, Copy)]
+pub struct LightSample {
+    /// Unit direction from the hit point towards the light
+    pub wi: Vec3,
+    /// Incoming radiance along `wi`, weighted by its visibility and sampling density
+    pub radiance: Color,
+    /// Solid angle density `wi` was sampled with,
+    /// 0 for lights that rays can never hit, like points and single directions
+    pub pdf: f64,
+}
+
+/// A light found along a direction leaving a hit point, see [`Light::intersect`].
+#[derive(Debug, Clone, Copy)]
+pub struct LightHit {
+    /// Radiance emitted towards the hit point
+    pub radiance: Color,
+    /// Distance from the hit point to the light
+    pub distance: f64,
+    /// Solid angle density of [`Light::sample`] choosing the direction
+    pub pdf: f64,
+}
+
 pub trait Light: Send + Sync {
-    // the direction of the incoming light at a hit point
-    fn get_direction(&self, hit: &Hit) -> Vec3;
-    fn radiance(&self, hit: &Hit) -> Color;
-    fn shadow_amount(&self, hit: &Hit) -> f64;
+    /// Returns an estimate of the power the light emits, used to pick bright lights more often.
+    /// Lights at infinity return the power falling onto a disk of unit radius.
+    fn power(&self) -> f64;
+
+    /// Samples the light arriving at a hit point.
+    ///
+    /// The light contributes the sum of `f(wi) * radiance * n·wi` over the samples.
+    /// Lights arriving from a single direction take one sample towards it.
+    fn sample(&self, hit: &Hit) -> Vec<LightSample>;
+
+    /// Returns whether rays can hit the light, so that [`Light::intersect`] may find it.
+    ///
+    /// Lights without an extent, like points and single directions, return `false`
+    /// and are only sampled from the light, never by the brdf.
+    fn has_area(&self) -> bool {
+        false
+    }
+
+    /// Finds the light along the unit direction `wi` from a hit point, ignoring occluders.
+    ///
+    /// Used to weight directions sampled from the brdf against [`Light::sample`].
+    /// The default never finds the light, which suits lights without [`Light::has_area`].
+    fn intersect(&self, _hit: &Hit, _wi: &Vec3) -> Option<LightHit> {
+        None
+    }
+
+    /// Solid angle density of [`Light::sample`] choosing the unit direction `wi`,
+    /// 0 if `wi` misses the light.
+    fn pdf(&self, hit: &Hit, wi: &Vec3) -> f64 {
+        self.intersect(hit, wi).map_or(0.0, |light| light.pdf)
+    }
 }
 
+/// Samples a light arriving at a hit point from the single direction `wi`.
+///
+/// Gives no sample if the light is behind the surface, black or fully shadowed.
+/// The radiance and the visible fraction of the light are only computed when needed,
+/// the latter last as it casts shadow rays.
+fn single_sample(
+    hit: &Hit,
+    wi: Vec3,
+    radiance: impl FnOnce() -> Color,
+    shadow_amount: impl FnOnce() -> f64,
+) -> Vec<LightSample> {
+    if hit.normal.dot(&wi) <= 0.0 {
+        return vec![];
+    }
+    let radiance = radiance();
+    if radiance == Color::zeros() {
+        return vec![];
+    }
+    let shadow = shadow_amount();
+    if shadow == 0.0 {
+        return vec![];
+    }
+    vec![LightSample { wi, radiance: radiance * shadow, pdf: 0.0 }]
+}
+
+/// Returns the attenuation `1 / d^falloff` of a point source at `distance` world units,
+/// with `d` the distance in metres.
+fn distance_attenuation(distance: f64, metres_per_unit: f64, falloff: f64) -> f64 {
+    (distance * metres_per_unit).powf(-falloff)
+}
+
+/// Casts a shadow ray from the hit point and tests whether it is blocked
+/// by a non-emissive object closer than `tmax`.
 #[must_use]
 pub fn in_shadow(hit: &Hit, dir: &Vec3, tmax: f64) -> bool {
+    stats::increment(Counter::ShadowRays);
+    occluded(hit, dir, tmax)
+}
+
+/// Returns the fraction of a light's disk that is visible from the hit point, for soft shadows.
+///
+/// Shadow rays are cast to the sample points of the disk, which faces the hit point.
+///
+/// # Arguments
+/// * `axis` - Unit direction from the hit point towards the center of the disk
+/// * `radius` - Radius of the disk relative to its distance, the tangent of its angular radius
+/// * `tmax` - Distance to the center of the disk, infinite for lights at infinity
+fn visible_fraction(hit: &Hit, axis: &Vec3, radius: f64, tmax: f64) -> f64 {
+    // Span the disk from the world axis least aligned with the direction
+    let u = axis.cross(&Vec3::ith(axis.iamin(), 1.0)).normalize();
+    let v = axis.cross(&u);
+    let sampler = &hit.renderer.sampler;
+    let total = sampler
+        .disk()
+        .filter(|(_, dp)| {
+            let to_point = axis + (u * dp.x + v * dp.y) * radius;
+            let length = to_point.norm();
+            !in_shadow(hit, &(to_point / length), tmax * length)
+        })
+        .count();
+    #[expect(clippy::cast_possible_truncation)]
+    (f64::from(total as u32) / f64::from(sampler.count()))
+}
+
+/// Same as [`in_shadow`] without counting the ray as a shadow ray.
+fn occluded(hit: &Hit, dir: &Vec3, tmax: f64) -> bool {
     let offset = 0.00001 * dir;
-    let shadow_ray = Ray::new(hit.hit_point + offset, *dir);
+    let shadow_ray = Ray::new(hit.hit_point + offset, *dir).with_time(hit.ray.time);
     hit.renderer
         .scene
         .intersects(&shadow_ray, 0.0, tmax)
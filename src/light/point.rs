@@ -1,27 +1,136 @@
+use std::{f64::consts::PI, sync::Arc};
+
 use nalgebra::{Point3, distance};
 
-use super::{Light, in_shadow};
-use crate::{color::Color, model::Vec3, ray::Hit};
+use super::{
+    IesProfile, Light, LightSample, distance_attenuation, in_shadow, single_sample,
+    visible_fraction,
+};
+use crate::{
+    color::{Color, luminance},
+    config::light::LUMENS_PER_WATT,
+    model::Vec3,
+    ray::Hit,
+};
 
+/// A light emitting equally in all directions from a point or a small sphere.
+///
+/// The radiance falls off with the distance `d` as `ls / d^falloff`, with the distance in metres.
+/// A falloff of 2 is the physically based inverse-square law, 0 disables the falloff.
+/// An optional IES profile shapes the intensity around the light, its nadir pointing down
+/// along the world's +y axis and its horizontal angle 0 along +x. The profile keeps the
+/// light's power: `ls` becomes the intensity averaged over all directions, and the file's
+/// absolute candela values are ignored.
 pub struct Point {
+    /// Radiant intensity in watts per steradian
     pub ls: f64,
     pub cl: Color,
     pub location: Point3<f64>,
+    /// Exponent of the distance falloff
+    pub falloff: f64,
+    /// Radius of the emitting sphere in world units, 0 for a point with hard shadows
+    pub radius: f64,
+    /// Length of a world unit in metres
+    pub metres_per_unit: f64,
+    /// Photometric web scaling the intensity by direction, relative to its average
+    pub ies: Option<Arc<IesProfile>>,
 }
 
-impl Light for Point {
+impl Point {
+    /// Creates a point light with inverse-square falloff and hard shadows.
+    ///
+    /// # Arguments
+    /// * `ls` - Radiant intensity in watts per steradian
+    /// * `cl` - Color of the light
+    /// * `location` - Position of the light
+    #[must_use]
+    pub const fn new(ls: f64, cl: Color, location: Point3<f64>) -> Self {
+        Self { ls, cl, location, falloff: 2.0, radius: 0.0, metres_per_unit: 1.0, ies: None }
+    }
+
+    /// Creates a point light emitting `watts` of radiant power.
+    #[must_use]
+    pub fn from_watts(watts: f64, cl: Color, location: Point3<f64>) -> Self {
+        // The power spreads over the full sphere of 4π steradians
+        Self::new(watts / (4.0 * PI), cl, location)
+    }
+
+    /// Creates a point light emitting `lumens` of luminous power,
+    /// converted to watts at the peak efficacy of 683 lm/W.
+    #[must_use]
+    pub fn from_lumens(lumens: f64, cl: Color, location: Point3<f64>) -> Self {
+        Self::from_watts(lumens / LUMENS_PER_WATT, cl, location)
+    }
+
+    /// Sets the exponent of the distance falloff, 2 by default.
+    #[must_use]
+    pub const fn with_falloff(mut self, falloff: f64) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Turns the light into a sphere of `radius` world units, which casts soft shadows.
+    #[must_use]
+    pub const fn with_radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the length of a world unit in metres, which scales the distance falloff.
+    #[must_use]
+    pub const fn with_metres_per_unit(mut self, metres_per_unit: f64) -> Self {
+        self.metres_per_unit = metres_per_unit;
+        self
+    }
+
+    /// Shapes the intensity with the photometric web of `profile`,
+    /// which has the light's intensity as its average and keeps its power.
+    #[must_use]
+    pub fn with_ies(mut self, profile: Arc<IesProfile>) -> Self {
+        self.ies = Some(profile);
+        self
+    }
+
+    /// Returns the unit direction from the hit point towards the light.
     fn get_direction(&self, hit: &Hit) -> Vec3 {
         (self.location - hit.hit_point).normalize()
     }
 
-    fn radiance(&self, _hit: &Hit) -> Color {
-        self.cl * self.ls
+    /// Returns the radiance arriving at the hit point, ignoring occluders.
+    fn radiance(&self, hit: &Hit) -> Color {
+        // Points inside the sphere receive the radiance at its surface
+        let d = distance(&self.location, &hit.hit_point).max(self.radius);
+        let web = self.ies.as_ref().map_or(1.0, |ies| {
+            let wo = (hit.hit_point - self.location).normalize();
+            ies.shape_along(&wo, &Vec3::y(), &Vec3::x())
+        });
+        self.cl * (self.ls * web * distance_attenuation(d, self.metres_per_unit, self.falloff))
     }
 
+    /// Returns the fraction of the light that is not blocked from the hit point.
     fn shadow_amount(&self, hit: &Hit) -> f64 {
         let direction = (self.location - hit.hit_point).normalize();
         let d = distance(&self.location, &hit.hit_point);
-        let b = in_shadow(hit, &direction, d);
-        f64::from(u32::from(!b))
+        if self.radius <= 0.0 {
+            return f64::from(u32::from(!in_shadow(hit, &direction, d)));
+        }
+        // Sample the disk of the sphere facing the hit point
+        visible_fraction(hit, &direction, self.radius / d, d)
+    }
+}
+
+impl Light for Point {
+    fn power(&self) -> f64 {
+        // A profile only redistributes the intensity
+        4.0 * PI * self.ls * luminance(&self.cl)
+    }
+
+    fn sample(&self, hit: &Hit) -> Vec<LightSample> {
+        single_sample(
+            hit,
+            self.get_direction(hit),
+            || self.radiance(hit),
+            || self.shadow_amount(hit),
+        )
     }
 }
@@ -0,0 +1,89 @@
+use std::{str::FromStr, sync::Arc};
+
+use serde::Serialize;
+
+use super::Light;
+use crate::{distribution::Distribution1D, sampler::Sampler};
+
+/// How the lights sampled at a shading point are chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LightSelection {
+    /// Every light, which is noise free but slows down with the number of lights
+    #[default]
+    All,
+    /// A few lights picked with equal probability
+    Uniform,
+    /// A few lights picked in proportion to their power
+    Power,
+}
+
+impl FromStr for LightSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "uniform" => Ok(Self::Uniform),
+            "power" => Ok(Self::Power),
+            _ => Err(format!("Unknown light selection '{s}', expected all, uniform or power")),
+        }
+    }
+}
+
+/// Picks the lights sampled at each shading point.
+///
+/// Lights are picked with replacement, and each pick is weighted by the reciprocal
+/// of the number of picks times its probability, so the expected light is unchanged.
+pub struct LightSampler {
+    selection: LightSelection,
+    /// Number of lights picked per shading point
+    picks: u8,
+    /// Probability of picking each light
+    distribution: Distribution1D,
+}
+
+impl LightSampler {
+    /// Creates a light sampler for `lights`.
+    ///
+    /// # Arguments
+    /// * `lights` - The lights of the scene
+    /// * `selection` - How lights are picked
+    /// * `picks` - Number of lights picked per shading point, unless all lights are sampled
+    #[must_use]
+    pub fn new(lights: &[Arc<dyn Light>], selection: LightSelection, picks: u8) -> Self {
+        let func = match selection {
+            LightSelection::Power => lights.iter().map(|light| light.power()).collect(),
+            LightSelection::All | LightSelection::Uniform => vec![1.0; lights.len()],
+        };
+        Self { selection, picks: picks.max(1), distribution: Distribution1D::new(func) }
+    }
+
+    /// Returns the probability of picking the light at `index` with a single pick.
+    #[must_use]
+    pub fn probability(&self, index: usize) -> f64 {
+        #[expect(clippy::cast_precision_loss)]
+        let count = self.distribution.count() as f64;
+        self.distribution.pdf_of(index) / count
+    }
+
+    /// Picks the lights for one shading point.
+    ///
+    /// Picks are stratified over the distribution, with one random number each from the
+    /// generator of the calling thread.
+    ///
+    /// # Returns
+    /// The indices of the picked lights and the weights of their contributions
+    pub fn pick(&self) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let count = self.distribution.count();
+        let all = self.selection == LightSelection::All;
+        let every = (0..if all { count } else { 0 }).map(|index| (index, 1.0));
+        let picks = if all || count == 0 { 0 } else { self.picks };
+        let picked = (0..picks).map(move |i| {
+            let u = (f64::from(i) + Sampler::uniform()) / f64::from(picks);
+            let (_, _, index) = self.distribution.sample(u);
+            (index, 1.0 / (f64::from(picks) * self.probability(index)))
+        });
+        every.chain(picked)
+    }
+}
@@ -0,0 +1,194 @@
+use std::f64::consts::PI;
+
+use image::{Rgb, Rgb32FImage};
+use nalgebra::Point2;
+
+use super::{Environment, equirectangular_direction, equirectangular_uv};
+use crate::{
+    color::Color,
+    config::light::{GROUND_ALBEDO, SKY_INTENSITY, SUN_ANGULAR_DIAMETER, SUN_LUMINANCE},
+    model::Vec3,
+};
+
+/// The clear sky model of Preetham, Shirley and Smits, "A Practical Analytic Model for Daylight".
+///
+/// The sky's luminance and chromaticity follow the Perez distribution around the sun,
+/// which is a disk of 0.53 degrees dimmed and reddened by the atmosphere.
+/// Below the horizon a gray ground reflects the sky.
+/// [`Sky::to_environment`] bakes sky and sun into an environment map,
+/// which lights the scene with importance sampling and is seen by rays leaving it.
+pub struct Sky {
+    /// Unit vector pointing towards the sun
+    sun_direction: Vec3,
+    /// Angle between the zenith and the sun in radians
+    theta_sun: f64,
+    turbidity: f64,
+    /// Perez coefficients of the luminance Y and the chromaticities x and y
+    perez: [[f64; 5]; 3],
+    /// Luminance in kcd/m² and chromaticity at the zenith
+    zenith: [f64; 3],
+}
+
+impl Sky {
+    /// Creates a clear sky.
+    ///
+    /// # Arguments
+    /// * `elevation` - Angle of the sun above the horizon in degrees, between 0 and 90
+    /// * `azimuth` - Angle of the sun around the vertical axis in degrees,
+    ///   0 along the world's +z axis and 90 along +x
+    /// * `turbidity` - Haziness of the atmosphere, 2 for a very clear sky to 10 for haze
+    #[must_use]
+    pub fn new(elevation: f64, azimuth: f64, turbidity: f64) -> Self {
+        let (elevation, azimuth) = (elevation.to_radians(), azimuth.to_radians());
+        // The world's -y axis points up
+        let sun_direction = Vec3::new(
+            elevation.cos() * azimuth.sin(),
+            -elevation.sin(),
+            elevation.cos() * azimuth.cos(),
+        );
+        let theta_sun = PI / 2.0 - elevation;
+        let t = turbidity;
+        let perez = [
+            [
+                0.1787f64.mul_add(t, -1.4630),
+                (-0.3554f64).mul_add(t, 0.4275),
+                (-0.0227f64).mul_add(t, 5.3251),
+                0.1206f64.mul_add(t, -2.5771),
+                (-0.0670f64).mul_add(t, 0.3703),
+            ],
+            [
+                (-0.0193f64).mul_add(t, -0.2592),
+                (-0.0665f64).mul_add(t, 0.0008),
+                (-0.0004f64).mul_add(t, 0.2125),
+                (-0.0641f64).mul_add(t, -0.8989),
+                (-0.0033f64).mul_add(t, 0.0452),
+            ],
+            [
+                (-0.0167f64).mul_add(t, -0.2608),
+                (-0.0950f64).mul_add(t, 0.0092),
+                (-0.0079f64).mul_add(t, 0.2102),
+                (-0.0441f64).mul_add(t, -1.6537),
+                (-0.0109f64).mul_add(t, 0.0529),
+            ],
+        ];
+        let chi = (4.0 / 9.0 - t / 120.0) * 2.0f64.mul_add(-theta_sun, PI);
+        let luminance = 4.0453f64.mul_add(t, -4.9710).mul_add(chi.tan(), -0.2155 * t) + 2.4192;
+        let chromaticity = |m: [[f64; 4]; 3]| {
+            let [t2, t1, t0] = m.map(|c| {
+                let [a, b, c, d] = c;
+                a.mul_add(theta_sun, b).mul_add(theta_sun, c).mul_add(theta_sun, d)
+            });
+            t2.mul_add(t * t, t1.mul_add(t, t0))
+        };
+        let x = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let y = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+        Self { sun_direction, theta_sun, turbidity, perez, zenith: [luminance, x, y] }
+    }
+
+    /// Returns the unit vector pointing towards the sun.
+    #[must_use]
+    pub const fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    /// Returns the radiance of the sky arriving from the unit direction `dir`, without the sun.
+    #[must_use]
+    pub fn radiance_along(&self, dir: &Vec3) -> Color {
+        let cos_theta = -dir.y;
+        if cos_theta < 0.0 {
+            // The ground reflects the sky above it
+            let up = Vec3::new(dir.x, -dir.y, dir.z);
+            return self.radiance_along(&up) * GROUND_ALBEDO;
+        }
+        // Keep the horizon finite, the Perez function diverges at cos θ = 0
+        let theta = cos_theta.max(0.01).acos();
+        let gamma = dir.dot(&self.sun_direction).clamp(-1.0, 1.0).acos();
+        let [luminance, x, y] = [0, 1, 2].map(|i| {
+            self.zenith[i] * self.perez_function(i, theta, gamma)
+                / self.perez_function(i, 0.0, self.theta_sun)
+        });
+        xyy_to_rgb(x, y, luminance) * SKY_INTENSITY
+    }
+
+    /// Returns the radiance of the sun's disk.
+    ///
+    /// The light is attenuated by Rayleigh scattering off air molecules
+    /// and by aerosols, which grow with the turbidity, along the path through the atmosphere.
+    #[must_use]
+    pub fn sun_radiance(&self) -> Color {
+        let theta_degrees = self.theta_sun.to_degrees();
+        // Relative optical mass of the air, which grows towards the horizon
+        let mass =
+            1.0 / (self.theta_sun.cos() + 0.15 * (93.885 - theta_degrees).max(1e-3).powf(-1.253));
+        let beta = 0.04608f64.mul_add(self.turbidity, -0.04586);
+        // Wavelengths in micrometres standing for red, green and blue
+        let transmittance = |lambda: f64| {
+            let rayleigh = (-0.008_735 * lambda.powf(-4.08) * mass).exp();
+            let aerosol = (-beta * lambda.powf(-1.3) * mass).exp();
+            rayleigh * aerosol
+        };
+        Color::new(transmittance(0.65), transmittance(0.57), transmittance(0.475))
+            * (SUN_LUMINANCE * SKY_INTENSITY)
+    }
+
+    /// Bakes the sky and the sun into an equirectangular environment map.
+    ///
+    /// The sun is smaller than a texel, so its power is added to the texel it lies in.
+    #[must_use]
+    pub fn to_environment(&self, width: u32, height: u32) -> Environment {
+        let (w, h) = (f64::from(width), f64::from(height));
+        let mut image = Rgb32FImage::from_fn(width, height, |x, y| {
+            let uv = Point2::new((f64::from(x) + 0.5) / w, (f64::from(y) + 0.5) / h);
+            let (dir, _) = equirectangular_direction(uv, 0.0);
+            to_texel(&self.radiance_along(&dir))
+        });
+
+        let uv = equirectangular_uv(&self.sun_direction, 0.0);
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (x, y) = (((uv.x * w) as u32).min(width - 1), ((uv.y * h) as u32).min(height - 1));
+        let texel_solid_angle = (2.0 * PI / w) * (PI / h) * (PI * (f64::from(y) + 0.5) / h).sin();
+        let sun_solid_angle = 2.0 * PI * (1.0 - (SUN_ANGULAR_DIAMETER.to_radians() / 2.0).cos());
+        let sun = self.sun_radiance() * (sun_solid_angle / texel_solid_angle);
+        let Rgb([r, g, b]) = *image.get_pixel(x, y);
+        let texel = Color::new(f64::from(r), f64::from(g), f64::from(b)) + sun;
+        image.put_pixel(x, y, to_texel(&texel));
+
+        Environment::from_image(&image)
+    }
+
+    /// Evaluates the Perez distribution of luminance or chromaticity `i`
+    /// at zenith angle `theta` and angle `gamma` from the sun.
+    fn perez_function(&self, i: usize, theta: f64, gamma: f64) -> f64 {
+        let [a, b, c, d, e] = self.perez[i];
+        a.mul_add((b / theta.cos()).exp(), 1.0)
+            * c.mul_add((d * gamma).exp(), e.mul_add(gamma.cos().powi(2), 1.0))
+    }
+}
+
+/// Converts CIE xyY to linear sRGB, clamping colors outside the gamut.
+fn xyy_to_rgb(x: f64, y: f64, luminance: f64) -> Color {
+    if y <= 0.0 {
+        return Color::zeros();
+    }
+    let (cx, cz) = (x / y * luminance, (1.0 - x - y) / y * luminance);
+    Color::new(
+        3.2406f64.mul_add(cx, (-1.5372f64).mul_add(luminance, -0.4986 * cz)),
+        (-0.9689f64).mul_add(cx, 1.8758f64.mul_add(luminance, 0.0415 * cz)),
+        0.0557f64.mul_add(cx, (-0.2040f64).mul_add(luminance, 1.0570 * cz)),
+    )
+    .map(|c| c.max(0.0))
+}
+
+/// Converts a color to a texel of a floating point image.
+#[expect(clippy::cast_possible_truncation)]
+fn to_texel(c: &Color) -> Rgb<f32> {
+    Rgb([c.x as f32, c.y as f32, c.z as f32])
+}
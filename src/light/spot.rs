@@ -0,0 +1,188 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use nalgebra::{Point2, Point3, distance};
+
+use super::{IesProfile, Light, LightSample, distance_attenuation, in_shadow, single_sample};
+use crate::{
+    color::{Color, luminance},
+    model::Vec3,
+    ray::Hit,
+    texture::ImageTexture,
+};
+
+/// A point light emitting into a cone.
+///
+/// Like a [`super::Point`], its radiance falls off with the distance `d` in metres
+/// as `ls / d^falloff`.
+/// Inside the inner cone the light has full intensity, between the inner and outer cone
+/// it fades out with a smoothstep, and outside the outer cone it is dark.
+/// An optional gobo texture is projected over the outer cone like a slide,
+/// and an optional IES profile shapes the intensity with its nadir along the cone's axis.
+/// Like for [`super::Point`], the profile is scaled to keep `ls` as its average intensity.
+pub struct Spot {
+    /// Radiant intensity along the axis in watts per steradian
+    pub ls: f64,
+    pub cl: Color,
+    pub location: Point3<f64>,
+    /// Exponent of the distance falloff
+    pub distance_falloff: f64,
+    /// Length of a world unit in metres
+    pub metres_per_unit: f64,
+    /// Axis of the cone, pointing away from the light
+    direction: Vec3,
+    /// Cosine of the half angle of the cone with full intensity
+    cos_inner: f64,
+    /// Cosine of the half angle of the cone beyond which the light is dark
+    cos_outer: f64,
+    /// Tangent of the outer half angle, the half extent of the gobo at unit distance
+    tan_outer: f64,
+    /// Directions of the gobo's columns and rows across the cone
+    right: Vec3,
+    down: Vec3,
+    gobo: Option<Arc<ImageTexture>>,
+    ies: Option<Arc<IesProfile>>,
+}
+
+impl Spot {
+    /// Creates a spot light.
+    ///
+    /// # Arguments
+    /// * `ls` - Radiant intensity along the axis in watts per steradian
+    /// * `cl` - Color of the light
+    /// * `location` - Position of the light
+    /// * `target` - Point the cone is aimed at
+    /// * `inner_angle` - Half angle of the cone with full intensity, in degrees
+    /// * `outer_angle` - Half angle of the cone that is lit at all, in degrees, below 90
+    #[must_use]
+    pub fn new(
+        ls: f64,
+        cl: Color,
+        location: Point3<f64>,
+        target: Point3<f64>,
+        inner_angle: f64,
+        outer_angle: f64,
+    ) -> Self {
+        let direction = (target - location).normalize();
+        // Orient the gobo like a camera at the light looking along the cone,
+        // see `Setting::compute_uvw`
+        let w = -direction;
+        let mut right = Vec3::y().cross(&w);
+        if right.norm_squared() < 1e-12 {
+            right = Vec3::z().cross(&w);
+        }
+        let right = right.normalize();
+        let down = w.cross(&right);
+        let outer = outer_angle.to_radians();
+        Self {
+            ls,
+            cl,
+            location,
+            distance_falloff: 2.0,
+            metres_per_unit: 1.0,
+            direction,
+            cos_inner: inner_angle.min(outer_angle).to_radians().cos(),
+            cos_outer: outer.cos(),
+            tan_outer: outer.tan(),
+            right,
+            down,
+            gobo: None,
+            ies: None,
+        }
+    }
+
+    /// Sets the exponent of the distance falloff, 2 by default.
+    #[must_use]
+    pub const fn with_distance_falloff(mut self, falloff: f64) -> Self {
+        self.distance_falloff = falloff;
+        self
+    }
+
+    /// Sets the length of a world unit in metres, which scales the distance falloff.
+    #[must_use]
+    pub const fn with_metres_per_unit(mut self, metres_per_unit: f64) -> Self {
+        self.metres_per_unit = metres_per_unit;
+        self
+    }
+
+    /// Projects `gobo` over the outer cone, its top towards the world's -y axis.
+    #[must_use]
+    pub fn with_gobo(mut self, gobo: Arc<ImageTexture>) -> Self {
+        self.gobo = Some(gobo);
+        self
+    }
+
+    /// Shapes the intensity with the photometric web of `profile`, its nadir along the axis
+    /// of the cone and its horizontal angle 0 towards the right of the gobo.
+    #[must_use]
+    pub fn with_ies(mut self, profile: Arc<IesProfile>) -> Self {
+        self.ies = Some(profile);
+        self
+    }
+
+    /// Returns the fraction of the light's intensity emitted along the unit vector `wo`,
+    /// pointing away from the light.
+    #[must_use]
+    pub fn falloff(&self, wo: &Vec3) -> Color {
+        let cos = wo.dot(&self.direction);
+        if cos <= self.cos_outer {
+            return Color::zeros();
+        }
+        let cone = if cos >= self.cos_inner {
+            1.0
+        } else {
+            let t = (cos - self.cos_outer) / (self.cos_inner - self.cos_outer);
+            t * t * 2.0f64.mul_add(-t, 3.0)
+        };
+        let cone = self
+            .ies
+            .as_ref()
+            .map_or(cone, |ies| cone * ies.shape_along(wo, &self.direction, &self.right));
+        let Some(gobo) = &self.gobo else {
+            return Color::repeat(cone);
+        };
+        // Intersect the plane at unit distance along the axis, spanning [-1, 1]² over the cone
+        let p = wo / cos / self.tan_outer;
+        let uv = Point2::new(p.dot(&self.right), p.dot(&self.down)).map(|c| c.mul_add(0.5, 0.5));
+        if uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0 {
+            return Color::zeros();
+        }
+        gobo.sample(uv) * cone
+    }
+
+    /// Returns the unit direction from the hit point towards the light.
+    fn get_direction(&self, hit: &Hit) -> Vec3 {
+        (self.location - hit.hit_point).normalize()
+    }
+
+    /// Returns the radiance arriving at the hit point, ignoring occluders.
+    fn radiance(&self, hit: &Hit) -> Color {
+        let wo = hit.hit_point - self.location;
+        let attenuation =
+            distance_attenuation(wo.norm(), self.metres_per_unit, self.distance_falloff);
+        self.falloff(&wo.normalize()).component_mul(&self.cl) * (self.ls * attenuation)
+    }
+
+    /// Returns the fraction of the light that is not blocked from the hit point.
+    fn shadow_amount(&self, hit: &Hit) -> f64 {
+        let direction = (self.location - hit.hit_point).normalize();
+        let d = distance(&self.location, &hit.hit_point);
+        let b = in_shadow(hit, &direction, d);
+        f64::from(u32::from(!b))
+    }
+}
+
+impl Light for Spot {
+    fn power(&self) -> f64 {
+        // The solid angle of the outer cone, a profile only redistributes the intensity
+        2.0 * PI * (1.0 - self.cos_outer) * self.ls * luminance(&self.cl)
+    }
+
+    fn sample(&self, hit: &Hit) -> Vec<LightSample> {
+        single_sample(
+            hit,
+            self.get_direction(hit),
+            || self.radiance(hit),
+            || self.shadow_amount(hit),
+        )
+    }
+}
@@ -19,6 +19,73 @@
 //! # Render with thin lens camera (depth of field)
 //! cargo run --release -- --camera thin-lens
 //!
+//! # Open the thin lens to f/2 and focus on the object seen at a pixel
+//! cargo run --release -- --f-number 2 --autofocus 250,400
+//!
+//! # Shape the bokeh with a six blade iris, or with a grayscale aperture mask
+//! cargo run --release -- --f-number 2 --aperture-blades 6 --aperture-rotation 15
+//! cargo run --release -- --f-number 2 --aperture-mask heart.png
+//!
+//! # Render through the elements of a real lens prescription
+//! cargo run --release -- --realistic-lens --lens-file assets/lenses/dgauss.50mm.dat
+//!
+//! # Render with a parallel projection
+//! cargo run --release -- --orthographic
+//!
+//! # Frame the scene with a wider field of view, rolled by 10 degrees
+//! cargo run --release -- --fov 70 --roll 10
+//!
+//! # Render a 360° panorama or a fisheye view
+//! cargo run --release -- --spherical --width 1000 --height 500
+//! cargo run --release -- --fisheye
+//!
+//! # Denoise a low sample count render
+//! cargo run --release -- --samples 4 --denoise
+//!
+//! # Inspect the scene with a debug tracer
+//! cargo run --release -- --debug-normals
+//! cargo run --release -- --debug-bvh-heatmap
+//!
+//! # Write a JSON report with timings and ray statistics
+//! cargo run --release -- --report report.json
+//!
+//! # Re-render only the top left quarter of the image
+//! cargo run --release -- --crop 0,0,250,250
+//! cargo run --release -- --crop 0.0,0.0,0.5,0.5 --crop-full-frame
+//!
+//! # Render a stereo pair for parallel viewing, or a red-cyan anaglyph
+//! cargo run --release -- --stereo side-by-side
+//! cargo run --release -- --stereo anaglyph --interocular-distance 0.2
+//!
+//! # Motion blur the moving sphere over a shutter interval
+//! cargo run --release -- --shutter-open 0 --shutter-close 1
+//!
+//! # Render a smooth camera flythrough as numbered frames
+//! cargo run --release -- --camera-path assets/animations/flythrough.cam \
+//!     --camera-interpolation catmull-rom --frames 0..48 --output frame_%04d.png
+//!
+//! # Let the sun shine in, with soft shadows from its 0.53 degree disk
+//! cargo run --release -- --directional-light --directional-light-diameter 0.53
+//!
+//! # Sample two lights per shading point, picked by power
+//! cargo run --release -- --point-light 1W --light-selection power --light-samples 2
+//!
+//! # Add a 1 watt spherical point light casting soft shadows
+//! cargo run --release -- --point-light 1W --point-light-radius 0.1
+//!
+//! # Light the large sphere with a spot light projecting a gobo
+//! cargo run --release -- --spot-light --gobo window.png
+//!
+//! # Shape the point and spot lights with IES photometric profiles
+//! cargo run --release -- --point-light 3400lm --point-light-ies assets/ies/downlight.ies
+//! cargo run --release -- --spot-light --spot-light-ies assets/ies/wallwash.ies
+//!
+//! # Light the box with an HDR environment map, seen through its open front
+//! cargo run --release -- --environment sky.hdr --environment-rotation 90
+//!
+//! # Light the box with a clear afternoon sky and a low sun
+//! cargo run --release -- --sky --sun-elevation 20 --turbidity 4
+//!
 //! # Custom resolution
 //! cargo run --release -- --width 1920 --height 1080
 //! ```
@@ -39,16 +106,24 @@ static GLOBAL: jemallocator::Jemalloc = jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-use std::time::Instant;
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use image::{RgbImage, imageops::flip_horizontal};
 use raytracing::{
     args::args,
+    camera::CameraPath,
     color::{Color, to_rgb},
-    config::render::{OUTPUT_FILENAME, PREVIEW_SAMPLES},
+    config::render::PREVIEW_SAMPLES,
+    denoiser::Denoiser,
     error::{RayTracingError, Result},
+    light::LightSelection,
     renderer::Renderer,
+    report::{Report, Timings},
     scene::SceneFactory,
+    stats::Stats,
 };
 
 /// Main entry point for the ray tracer.
@@ -58,11 +133,15 @@ use raytracing::{
 /// 2. Set up the scene with geometry, lights, and camera
 /// 3. Create renderer with appropriate sampling strategy
 /// 4. Perform parallel ray tracing
-/// 5. Save the result as PNG
+/// 5. Optionally denoise the image
+/// 6. Save the result as PNG
+/// 7. Optionally write a JSON report
 ///
 /// The renderer uses Rayon for parallel pixel processing,
 /// making it scale well with available CPU cores.
 fn main() -> Result<()> {
+    let start = Instant::now();
+
     // Parse command-line arguments
     let args = args().run();
 
@@ -71,21 +150,75 @@ fn main() -> Result<()> {
 
     // Initialize the scene and renderer
     println!("🎬 Initializing scene...");
+    let now = Instant::now();
     let scene = SceneFactory::create_scene(&args)?;
-    let renderer = Renderer::new(scene, &args);
+    let scene_load_time = now.elapsed();
+    let bvh_build_time = scene.bvh_build_time();
+    let mut renderer = Renderer::new(scene, &args);
+    let camera_path = args
+        .camera_path
+        .as_deref()
+        .map(|path| CameraPath::from_file(path, args.camera_interpolation))
+        .transpose()?;
 
     // Display rendering configuration
     print_config(&args);
 
-    // Perform the actual rendering with timing
-    let now = Instant::now();
-    let pixels = renderer.render();
-    let duration = now.elapsed();
+    // Render every frame with the same scene and BVH, only the camera moves
+    let mut render_time = Duration::ZERO;
+    let mut denoise_time = Duration::ZERO;
+    let mut outputs = vec![];
+    for frame in args.frames() {
+        if let Some(camera_path) = &camera_path {
+            renderer.scene.set_camera_key(&camera_path.at(f64::from(frame)), &args)?;
+        }
+
+        // Perform the actual rendering with timing
+        let now = Instant::now();
+        let pixels = renderer.render();
+        render_time += now.elapsed();
+
+        // Filter out Monte Carlo noise using the albedo, normal and depth buffers
+        let now = Instant::now();
+        let pixels = if args.denoise {
+            println!("🧹 Denoising...");
+            Denoiser::default().denoise(&pixels, &renderer.render_features())
+        } else {
+            pixels
+        };
+        if args.denoise {
+            denoise_time += now.elapsed();
+        }
 
-    print_stats(duration, &args);
+        // Save the rendered image, either just the crop window or the full frame
+        let output = args.output_path(frame);
+        let region = renderer.region();
+        if args.crop_full_frame {
+            let pixels = renderer.expand_to_full_frame(&pixels);
+            let (width, height) = (renderer.scene.view_width(), renderer.scene.view_height());
+            save_image(&pixels, width, height, &output)?;
+        } else {
+            save_image(&pixels, region.width(), region.height(), &output)?;
+        }
+        outputs.push(output);
+    }
 
-    // Save the rendered image
-    save_image(&pixels, &args)?;
+    let stats = renderer.stats();
+    print_stats(render_time, &stats, &args)?;
+
+    if let Some(path) = &args.report {
+        let timings = Timings {
+            scene_load: scene_load_time.saturating_sub(bvh_build_time).as_secs_f64(),
+            bvh_build: bvh_build_time.as_secs_f64(),
+            render: render_time.as_secs_f64(),
+            denoise: denoise_time.as_secs_f64(),
+            total: start.elapsed().as_secs_f64(),
+        };
+        let mut report = Report::new(&args, &renderer, timings, stats);
+        report.outputs = outputs;
+        report.write(path)?;
+        println!("📝 Report saved as {}", path.display());
+    }
 
     Ok(())
 }
@@ -95,37 +228,122 @@ fn print_config(args: &raytracing::args::Args) {
     println!("📋 Configuration:");
     println!("  Resolution: {}x{}", args.width, args.height);
     println!("  Camera: {:?}", args.camera);
+    if matches!(args.camera, raytracing::args::ArgCamera::ThinLens) {
+        println!("  Lens: {} mm f/{}", args.focal_length, args.f_number);
+    }
+    if matches!(args.camera, raytracing::args::ArgCamera::Orthographic) {
+        println!("  View width: {}", args.orthographic_width);
+    }
+    if matches!(args.camera, raytracing::args::ArgCamera::Fisheye) {
+        println!("  Field of view: {} degrees", args.fisheye_fov);
+    }
+    if matches!(args.camera, raytracing::args::ArgCamera::Spherical) {
+        println!(
+            "  Field of view: {} by {} degrees",
+            args.spherical_horizontal_fov, args.spherical_vertical_fov
+        );
+    }
+    println!("  Tracer: {:?}", args.tracer);
     println!("  Samples per pixel: {}", if args.preview { PREVIEW_SAMPLES } else { args.samples });
     println!("  Max ray depth: {}", if args.preview { 1 } else { 5 });
     println!("  Mode: {}", if args.preview { "Preview" } else { "Production" });
+    println!("  Denoise: {}", args.denoise);
+    if let Some(stereo) = &args.stereo {
+        println!("  Stereo: {stereo:?}, interocular distance {}", args.interocular_distance);
+    }
+    if let Some(crop) = &args.crop {
+        println!("  Crop: {},{} to {},{}", crop.x0, crop.y0, crop.x1, crop.y1);
+    }
+    if args.directional_light {
+        println!("  Directional light: diameter {}°", args.directional_light_diameter);
+    }
+    let path_or_none = |path: &Option<PathBuf>| {
+        path.as_ref().map_or("none".into(), |path| path.display().to_string())
+    };
+    if let Some(power) = &args.point_light {
+        println!(
+            "  Point light: {power:?}, radius {}, falloff {}, IES {}",
+            args.point_light_radius,
+            args.point_light_falloff,
+            path_or_none(&args.point_light_ies)
+        );
+    }
+    if args.spot_light {
+        println!(
+            "  Spot light: gobo {}, IES {}",
+            path_or_none(&args.gobo),
+            path_or_none(&args.spot_light_ies)
+        );
+    }
+    if let Some(path) = &args.environment {
+        println!(
+            "  Environment: {}, intensity {}, rotation {}°",
+            path.display(),
+            args.environment_intensity,
+            args.environment_rotation
+        );
+    }
+    if args.sky {
+        println!(
+            "  Sky: sun elevation {}°, azimuth {}°, turbidity {}",
+            args.sun_elevation, args.sun_azimuth, args.turbidity
+        );
+    }
+    if args.light_selection != LightSelection::All {
+        println!("  Light selection: {:?}, {} per point", args.light_selection, args.light_samples);
+    }
+    if let Some(frames) = &args.frames {
+        println!("  Frames: {}..{} as {}", frames.start, frames.end, args.output);
+    }
+    if let Some(path) = &args.camera_path {
+        println!("  Camera path: {} ({:?})", path.display(), args.camera_interpolation);
+    }
 }
 
 /// Prints rendering statistics after completion.
-fn print_stats(duration: std::time::Duration, args: &raytracing::args::Args) {
-    let total_rays = args.width
-        * args.height
-        * u32::from(if args.preview { PREVIEW_SAMPLES } else { args.samples });
-    let rays_per_sec = f64::from(total_rays) / duration.as_secs_f64();
+fn print_stats(
+    duration: std::time::Duration,
+    stats: &Stats,
+    args: &raytracing::args::Args,
+) -> Result<()> {
+    if args.stats_json {
+        let json = serde_json::to_string_pretty(stats)
+            .map_err(|e| RayTracingError::RenderError(format!("Failed to serialize stats: {e}")))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    let total_rays = stats.total_rays();
+    #[expect(clippy::cast_precision_loss)]
+    let rays_per_sec = total_rays as f64 / duration.as_secs_f64();
 
     println!("✅ Render completed in {}.{:03}s", duration.as_secs(), duration.subsec_millis());
     println!("   Total rays: {total_rays}");
+    println!("     Camera: {}", stats.camera_rays);
+    println!("     Reflection: {}", stats.reflection_rays);
+    println!("     Shadow: {}", stats.shadow_rays);
+    println!("     Occlusion: {}", stats.occlusion_rays);
+    println!("   BVH nodes visited: {}", stats.bvh_nodes);
+    println!("   AABB tests: {}", stats.aabb_tests);
+    println!("   Primitive tests: {}", stats.primitive_tests);
     println!("   Performance: {rays_per_sec:.0} rays/second");
+    Ok(())
 }
 
 /// Saves the rendered pixels as a PNG image.
 ///
 /// The image is flipped horizontally to match the expected orientation
 /// (ray tracer uses a different coordinate system than image formats).
-fn save_image(pixels: &[Color], args: &raytracing::args::Args) -> Result<()> {
+fn save_image(pixels: &[Color], width: u32, height: u32, path: &str) -> Result<()> {
     println!("💾 Saving image...");
 
     let rgb_data: Vec<u8> = pixels.iter().flat_map(to_rgb).collect();
-    let image = RgbImage::from_vec(args.width, args.height, rgb_data).ok_or_else(|| {
+    let image = RgbImage::from_vec(width, height, rgb_data).ok_or_else(|| {
         RayTracingError::RenderError("Failed to create image from pixel data".to_string())
     })?;
 
-    flip_horizontal(&image).save(OUTPUT_FILENAME)?;
+    flip_horizontal(&image).save(path)?;
 
-    println!("📸 Image saved as {OUTPUT_FILENAME}");
+    println!("📸 Image saved as {path}");
     Ok(())
 }
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use nalgebra::Point2;
+
+use super::Material;
+use crate::{
+    color::{Color, luminance},
+    model::Vec3,
+    ray::Hit,
+    texture::ImageTexture,
+};
+
+/// A material whose shading normal is tilted by a bump map.
+///
+/// The bump map is a height field read from the luminance of a texture. The normal leans
+/// away from the slope of the heights along the texture axes, measured per texel and scaled
+/// by `strength`, so flat geometry shows the relief of the texture.
+pub struct BumpMapped<M: Material> {
+    pub material: M,
+    bump: Arc<ImageTexture>,
+    /// Slope scaling factor
+    pub strength: f64,
+}
+
+impl<M: Material> BumpMapped<M> {
+    #[must_use]
+    pub const fn new(material: M, bump: Arc<ImageTexture>, strength: f64) -> Self {
+        Self { material, bump, strength }
+    }
+
+    /// Returns the hit with its normal tilted by the bump map.
+    fn bumped<'a>(&self, hit: &Hit<'a>) -> Hit<'a> {
+        let n = hit.normal;
+        let tangent = |d: &Vec3| {
+            let t = d - n * n.dot(d);
+            (t.norm_squared() > 0.0).then(|| t.normalize())
+        };
+        let (Some(t), Some(b)) = (tangent(&hit.dpdu), tangent(&hit.dpdv)) else {
+            return *hit;
+        };
+
+        let (width, height) = self.bump.dimensions();
+        let (du, dv) = (1.0 / f64::from(width), 1.0 / f64::from(height));
+        let uv = hit.texcoords;
+        let h = |u: f64, v: f64| {
+            luminance(&self.bump.sample(Point2::new(u, v).map(|c| c.rem_euclid(1.0))))
+        };
+        // Central differences, in height per texel
+        let slope_u = (h(uv.x + du, uv.y) - h(uv.x - du, uv.y)) / 2.0;
+        let slope_v = (h(uv.x, uv.y + dv) - h(uv.x, uv.y - dv)) / 2.0;
+        let normal = (n - (t * slope_u + b * slope_v) * self.strength).normalize();
+        Hit { normal, ..*hit }
+    }
+}
+
+impl<M: Material> Material for BumpMapped<M> {
+    fn shade(&self, hit: &Hit) -> Color {
+        self.material.shade(&self.bumped(hit))
+    }
+
+    fn emissive(&self) -> bool {
+        self.material.emissive()
+    }
+
+    fn ambient(&self) -> Color {
+        self.material.ambient()
+    }
+
+    fn albedo(&self) -> Color {
+        self.material.albedo()
+    }
+
+    fn albedo_at(&self, texcoords: Point2<f64>) -> Color {
+        self.material.albedo_at(texcoords)
+    }
+
+    fn diffuse(&self, hit: &Hit, wi: &Vec3) -> Color {
+        self.material.diffuse(&self.bumped(hit), wi)
+    }
+
+    fn specular(&self, hit: &Hit, wi: &Vec3) -> Color {
+        self.material.specular(&self.bumped(hit), wi)
+    }
+
+    fn reflective(&self, hit: &Hit) -> Color {
+        self.material.reflective(&self.bumped(hit))
+    }
+
+    fn sample_wi(&self, hit: &Hit, u: Point2<f64>) -> Option<Vec3> {
+        self.material.sample_wi(&self.bumped(hit), u)
+    }
+
+    fn pdf(&self, hit: &Hit, wi: &Vec3) -> f64 {
+        self.material.pdf(&self.bumped(hit), wi)
+    }
+}
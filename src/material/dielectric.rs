@@ -0,0 +1,86 @@
+use super::Material;
+use crate::{
+    brdf::{Brdf, GlossySpecular},
+    color::Color,
+    model::Vec3,
+    ray::{Hit, Ray},
+    stats::{self, Counter},
+};
+
+/// Offset of secondary rays from the surface, so they do not hit it again
+const SURFACE_OFFSET: f64 = 1e-6;
+
+/// A transparent material like glass or water (Chapter 28).
+///
+/// Light is split between a mirror reflection and a refraction by the Fresnel equations,
+/// and the refracted light is filtered by a color. Highlights of the lights are added by
+/// a glossy lobe. Shadows of dielectrics are opaque.
+pub struct Dielectric {
+    /// Index of refraction inside the material, relative to the outside
+    pub ior: f64,
+    /// Filter color of the transmitted light
+    pub cf: Color,
+    pub specular_brdf: GlossySpecular,
+}
+
+impl Dielectric {
+    #[must_use]
+    pub const fn new(ior: f64, cf: Color, specular_brdf: GlossySpecular) -> Self {
+        Self { ior, cf, specular_brdf }
+    }
+
+    /// Returns the fraction of light reflected at a boundary.
+    ///
+    /// # Arguments
+    /// * `cos_i` - Cosine of the angle between the incident direction and the normal
+    /// * `eta` - Index of refraction on the far side of the boundary relative to the near side
+    ///
+    /// # Returns
+    /// The reflectance, and the cosine of the refracted direction unless it is totally
+    /// internally reflected
+    #[must_use]
+    pub fn fresnel(cos_i: f64, eta: f64) -> (f64, Option<f64>) {
+        let cos_t2 = 1.0 - (1.0 - cos_i * cos_i) / (eta * eta);
+        if cos_t2 < 0.0 {
+            return (1.0, None);
+        }
+        let cos_t = cos_t2.sqrt();
+        let parallel = eta.mul_add(cos_i, -cos_t) / eta.mul_add(cos_i, cos_t);
+        let perpendicular = eta.mul_add(-cos_t, cos_i) / eta.mul_add(cos_t, cos_i);
+        (parallel.mul_add(parallel, perpendicular * perpendicular) / 2.0, Some(cos_t))
+    }
+
+    /// Traces a secondary ray leaving the hit point along `dir`.
+    fn trace(hit: &Hit, dir: Vec3) -> Color {
+        let side = hit.normal * SURFACE_OFFSET * hit.normal.dot(&dir).signum();
+        let ray = Ray::new(hit.hit_point + side, dir).with_time(hit.ray.time);
+        stats::increment(Counter::ReflectionRays);
+        hit.renderer.trace(&ray, hit.depth + 1)
+    }
+}
+
+impl Material for Dielectric {
+    fn albedo(&self) -> Color {
+        self.cf
+    }
+
+    fn specular(&self, hit: &Hit, wi: &Vec3) -> Color {
+        self.specular_brdf.f(hit, wi)
+    }
+
+    fn reflective(&self, hit: &Hit) -> Color {
+        let wo = -hit.ray.dir;
+        let cos_i = hit.normal.dot(&wo);
+        // Rays leaving the material see the inverse index
+        let eta = if hit.front_face { self.ior } else { self.ior.recip() };
+        let (kr, cos_t) = Self::fresnel(cos_i, eta);
+
+        let reflected = hit.normal * (2.0 * cos_i) - wo;
+        let mut color = Self::trace(hit, reflected) * kr;
+        if let Some(cos_t) = cos_t {
+            let transmitted = (-wo / eta + hit.normal * (cos_i / eta - cos_t)).normalize();
+            color += Self::trace(hit, transmitted).component_mul(&self.cf) * (1.0 - kr);
+        }
+        color
+    }
+}
@@ -1,6 +1,7 @@
 use super::Material;
 use crate::{color::Color, ray::Hit};
 
+#[derive(Debug, Clone, Copy)]
 pub struct Emissive {
     pub ls: f64, // radiance scaling factor
     pub ce: Color,
@@ -26,4 +27,8 @@ impl Material for Emissive {
     fn emissive(&self) -> bool {
         true
     }
+
+    fn albedo(&self) -> Color {
+        self.ce
+    }
 }
@@ -1,3 +1,5 @@
+use nalgebra::Point2;
+
 use super::Material;
 use crate::{
     brdf::{Brdf, Lambertian},
@@ -24,11 +26,23 @@ impl Material for Matte {
         self.diffuse_brdf.rho()
     }
 
+    fn albedo(&self) -> Color {
+        self.diffuse_brdf.rho()
+    }
+
     fn diffuse(&self, hit: &Hit, _wi: &Vec3) -> Color {
         let z = Color::zeros();
         self.diffuse_brdf.f(hit, &z)
     }
 
+    fn sample_wi(&self, hit: &Hit, u: Point2<f64>) -> Option<Vec3> {
+        self.diffuse_brdf.sample_wi(hit, u)
+    }
+
+    fn pdf(&self, hit: &Hit, wi: &Vec3) -> f64 {
+        self.diffuse_brdf.pdf(hit, wi)
+    }
+
     // for path tracing (Chapter 26)
     // fn reflective(&self, hit: &Hit) -> Color {
     // let mut wi = Vec3::zeros();
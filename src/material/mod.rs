@@ -7,19 +7,38 @@
 //! - `Matte`: Purely diffuse (Lambertian) surfaces
 //! - `Phong`: Classic Phong shading with ambient, diffuse, and specular components
 //! - `Reflective`: Materials with perfect or glossy reflections
+//! - `Dielectric`: Transparent surfaces that reflect and refract, like glass
+//! - `Textured`: Any of the above with its surface color read from an image texture
+//! - `BumpMapped`: Any material with its shading normal tilted by a height texture
 //! - `Emissive`: Light-emitting surfaces
 
+mod bump_mapped;
+mod dielectric;
 mod emissive;
 mod matte;
 mod phong;
 mod reflective;
+mod textured;
 
+pub use bump_mapped::*;
+pub use dielectric::*;
 pub use emissive::*;
 pub use matte::*;
 pub use phong::*;
 pub use reflective::*;
+pub use textured::*;
 
-use crate::{color::Color, light::Light, model::Vec3, ray::Hit};
+use std::sync::Arc;
+
+use nalgebra::Point2;
+
+use crate::{
+    brdf::{Brdf, GlossySpecular, Lambertian},
+    color::Color,
+    light::{Light, in_shadow},
+    model::Vec3,
+    ray::Hit,
+};
 
 /// Trait for all materials that define surface properties.
 ///
@@ -50,6 +69,21 @@ pub trait Material: Send + Sync {
         Color::zeros()
     }
 
+    /// Returns the surface albedo used as a denoiser feature.
+    ///
+    /// This is the reflectance of the surface independent of lighting.
+    fn albedo(&self) -> Color {
+        Color::zeros()
+    }
+
+    /// Returns the surface albedo at the texture coordinates of a hit point.
+    ///
+    /// Textured materials vary their albedo over the surface, so the denoiser keeps
+    /// their detail. Other materials return [`Material::albedo`].
+    fn albedo_at(&self, _texcoords: Point2<f64>) -> Color {
+        self.albedo()
+    }
+
     /// Computes the diffuse (Lambertian) reflection.
     ///
     /// # Arguments
@@ -74,17 +108,137 @@ pub trait Material: Send + Sync {
     fn reflective(&self, _hit: &Hit) -> Color {
         Color::zeros()
     }
+
+    /// Maps a point in the unit square to an incoming direction distributed like
+    /// [`Material::pdf`], used to importance sample direct lighting by the brdf.
+    ///
+    /// # Returns
+    /// The direction, or `None` if the material cannot be sampled or the direction is below
+    /// the surface
+    fn sample_wi(&self, _hit: &Hit, _u: Point2<f64>) -> Option<Vec3> {
+        None
+    }
+
+    /// Solid angle density of [`Material::sample_wi`] choosing `wi`.
+    fn pdf(&self, _hit: &Hit, _wi: &Vec3) -> f64 {
+        0.0
+    }
+}
+
+/// Materials shared by many objects, like all triangles of a mesh.
+impl<M: Material + ?Sized> Material for Arc<M> {
+    fn shade(&self, hit: &Hit) -> Color {
+        (**self).shade(hit)
+    }
+
+    fn emissive(&self) -> bool {
+        (**self).emissive()
+    }
+
+    fn ambient(&self) -> Color {
+        (**self).ambient()
+    }
+
+    fn albedo(&self) -> Color {
+        (**self).albedo()
+    }
+
+    fn albedo_at(&self, texcoords: Point2<f64>) -> Color {
+        (**self).albedo_at(texcoords)
+    }
+
+    fn diffuse(&self, hit: &Hit, wi: &Vec3) -> Color {
+        (**self).diffuse(hit, wi)
+    }
+
+    fn specular(&self, hit: &Hit, wi: &Vec3) -> Color {
+        (**self).specular(hit, wi)
+    }
+
+    fn reflective(&self, hit: &Hit) -> Color {
+        (**self).reflective(hit)
+    }
+
+    fn sample_wi(&self, hit: &Hit, u: Point2<f64>) -> Option<Vec3> {
+        (**self).sample_wi(hit, u)
+    }
+
+    fn pdf(&self, hit: &Hit, wi: &Vec3) -> f64 {
+        (**self).pdf(hit, wi)
+    }
+}
+
+/// Estimates the direct light from `light` with directions sampled from the material's brdf,
+/// weighted against light sampling with the power heuristic.
+fn brdf_sampled_light<M: Material + ?Sized>(m: &M, hit: &Hit, light: &dyn Light) -> Color {
+    let sampler = &hit.renderer.sampler;
+    let n = f64::from(sampler.count());
+    sampler
+        .square()
+        .filter_map(|u| {
+            let wi = m.sample_wi(hit, u)?;
+            let ndotwi = hit.normal.dot(&wi);
+            let pdf = m.pdf(hit, &wi);
+            if ndotwi <= 0.0 || pdf <= 0.0 {
+                return None;
+            }
+            let found = light.intersect(hit, &wi)?;
+            if in_shadow(hit, &wi, found.distance) {
+                return None;
+            }
+            let weight = power_heuristic(pdf, found.pdf);
+            let material_response = m.diffuse(hit, &wi) + m.specular(hit, &wi);
+            Some(material_response.component_mul(&found.radiance) * (ndotwi * weight / pdf / n))
+        })
+        .sum()
+}
+
+/// Samples a diffuse and a glossy lobe, each picked in proportion to its coefficient.
+fn sample_lobes(
+    diffuse: &Lambertian,
+    specular: &GlossySpecular,
+    hit: &Hit,
+    u: Point2<f64>,
+) -> Option<Vec3> {
+    let pd = diffuse_probability(diffuse, specular)?;
+    if u.x < pd {
+        diffuse.sample_wi(hit, Point2::new(u.x / pd, u.y))
+    } else {
+        specular.sample_wi(hit, Point2::new((u.x - pd) / (1.0 - pd), u.y))
+    }
+}
+
+/// Density of [`sample_lobes`] choosing `wi`.
+fn lobes_pdf(diffuse: &Lambertian, specular: &GlossySpecular, hit: &Hit, wi: &Vec3) -> f64 {
+    diffuse_probability(diffuse, specular)
+        .map_or(0.0, |pd| pd.mul_add(diffuse.pdf(hit, wi), (1.0 - pd) * specular.pdf(hit, wi)))
+}
+
+/// Probability of sampling the diffuse lobe, `None` if neither lobe reflects.
+fn diffuse_probability(diffuse: &Lambertian, specular: &GlossySpecular) -> Option<f64> {
+    let total = diffuse.kd + specular.ks;
+    (total > 0.0).then(|| diffuse.kd / total)
+}
+
+/// Weight of a sample drawn with density `pdf` against another strategy's density `other`,
+/// the power heuristic with exponent 2.
+fn power_heuristic(pdf: f64, other: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other * other);
+    if a + b > 0.0 { a / (a + b) } else { 0.0 }
 }
 
 /// Generic shading function that computes the final color for any material.
 ///
 /// This implements the core shading equation:
-/// `color = ambient + Σ(lights) [N·L * (diffuse + specular) * radiance * shadow] + reflective`
+/// `color = ambient + Σ(lights) Σ(samples) [w * N·L * (diffuse + specular) * radiance] + reflective`
 ///
 /// Where:
-/// - N·L is the cosine of the angle between surface normal and light direction
-/// - radiance is the incoming light intensity
-/// - shadow is the shadow attenuation factor
+/// - the samples are drawn from both [`Light::sample`] and [`Material::sample_wi`],
+///   the latter only for lights with [`Light::has_area`]
+/// - w is the power heuristic weight of multiple importance sampling between the two
+/// - N·L is the cosine of the angle between surface normal and the sampled light direction
+/// - radiance is the incoming light intensity of the sample, including its shadowing
+///   and sampling density
 ///
 /// # Arguments
 /// * `m` - The material to shade
@@ -93,56 +247,48 @@ pub fn shade<M: Material + ?Sized>(m: &M, hit: &Hit) -> Color {
     // Optimized shading with better cache locality and vectorization
 
     // Start with ambient contribution
-    let mut total_color =
-        m.ambient().component_mul(&hit.renderer.scene.ambient_light().radiance(hit));
+    let mut total_color = m.ambient().component_mul(&hit.renderer.scene.ambient_light().radiance());
 
     // Pre-compute reflective contribution once (often zero, so check first)
     let reflective = m.reflective(hit);
     let has_reflection = reflective != Color::zeros();
 
-    // Accumulate light contributions with better loop efficiency
-    for light in hit.renderer.scene.lights() {
-        // Get direction from surface to light
-        let wi = light.get_direction(hit);
+    // Accumulate the contributions of the picked lights, sampled from both the lights and the brdf
+    let lights = hit.renderer.scene.lights();
+    for (index, pick_weight) in hit.renderer.light_sampler.pick() {
+        let light = lights[index].as_ref();
+        let mut light_color = Color::zeros();
 
-        // Calculate angle between surface normal and light direction
-        let ndotwi = hit.normal.dot(&wi);
+        for sample in &light.sample(hit) {
+            // Calculate angle between surface normal and light direction
+            let ndotwi = hit.normal.dot(&sample.wi);
 
-        // Early exit if light is behind the surface
-        if ndotwi <= 0.0 {
-            continue;
-        }
+            // Skip samples behind the surface
+            if ndotwi <= 0.0 {
+                continue;
+            }
 
-        // Get light intensity at hit point
-        let radiance = light.radiance(hit);
+            // Lights that rays cannot hit are only found by light sampling
+            let weight = if sample.pdf > 0.0 {
+                power_heuristic(sample.pdf, m.pdf(hit, &sample.wi))
+            } else {
+                1.0
+            };
 
-        // Early exit if no radiance
-        if radiance == Vec3::zeros() {
-            continue;
+            // Compute material response to light
+            let material_response = m.diffuse(hit, &sample.wi) + m.specular(hit, &sample.wi);
+            light_color += material_response.component_mul(&sample.radiance) * ndotwi * weight;
         }
 
-        // Calculate shadow attenuation
-        let shadow = light.shadow_amount(hit);
-
-        // Skip if completely in shadow
-        if shadow == 0.0 {
-            continue;
+        if light.has_area() {
+            light_color += brdf_sampled_light(m, hit, light);
         }
+        total_color += light_color * pick_weight;
+    }
 
-        // Compute material response to light
-        let diffuse = m.diffuse(hit, &wi);
-        let specular = m.specular(hit, &wi);
-
-        // Apply lighting equation with optimized operations
-        // Combine operations to improve cache usage and vectorization
-        let material_response = diffuse + specular;
-        let light_contribution = radiance * shadow * ndotwi;
-        total_color += material_response.component_mul(&light_contribution);
-
-        // Add reflection contribution per light
-        if has_reflection {
-            total_color += reflective;
-        }
+    // Add the reflection once, it does not depend on the lights
+    if has_reflection {
+        total_color += reflective;
     }
 
     total_color
@@ -1,9 +1,12 @@
-use super::Material;
+use nalgebra::Point2;
+
+use super::{Material, lobes_pdf, sample_lobes};
 use crate::{
     brdf::{Brdf, GlossySpecular, Lambertian},
     color::Color,
     model::Vec3,
     ray::{Hit, Ray},
+    stats::{self, Counter},
 };
 
 pub struct Phong {
@@ -34,6 +37,10 @@ impl Material for Phong {
         self.ambient_brdf.rho()
     }
 
+    fn albedo(&self) -> Color {
+        self.diffuse_brdf.rho() + self.specular_brdf.cs * self.specular_brdf.ks
+    }
+
     fn diffuse(&self, hit: &Hit, wi: &Vec3) -> Color {
         self.diffuse_brdf.f(hit, wi)
     }
@@ -49,8 +56,17 @@ impl Material for Phong {
         let mut wi = Vec3::zeros();
         let mut pdf = 0.0;
         let fr = self.specular_brdf.sample_f(hit, &mut wi, &mut pdf);
-        let reflected_ray = Ray::new(hit.hit_point, wi);
+        let reflected_ray = Ray::new(hit.hit_point, wi).with_time(hit.ray.time);
+        stats::increment(Counter::ReflectionRays);
         hit.renderer.trace(&reflected_ray, hit.depth + 1).component_mul(&fr) * hit.normal.dot(&wi)
             / pdf
     }
+
+    fn sample_wi(&self, hit: &Hit, u: Point2<f64>) -> Option<Vec3> {
+        sample_lobes(&self.diffuse_brdf, &self.specular_brdf, hit, u)
+    }
+
+    fn pdf(&self, hit: &Hit, wi: &Vec3) -> f64 {
+        lobes_pdf(&self.diffuse_brdf, &self.specular_brdf, hit, wi)
+    }
 }
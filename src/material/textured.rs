@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use nalgebra::Point2;
+
+use super::{Dielectric, Material, Matte, Phong, Reflective};
+use crate::{color::Color, model::Vec3, ray::Hit, texture::ImageTexture};
+
+/// Materials whose surface color can be multiplied by a texture color.
+pub trait Tint {
+    /// Returns the material with its surface color multiplied by `color`.
+    #[must_use]
+    fn tinted(&self, color: &Color) -> Self;
+}
+
+impl Tint for Matte {
+    fn tinted(&self, color: &Color) -> Self {
+        Self::new(self.ambient_brdf.tinted(color), self.diffuse_brdf.tinted(color))
+    }
+}
+
+/// Only the diffuse lobe takes the texture, highlights keep their own color.
+impl Tint for Phong {
+    fn tinted(&self, color: &Color) -> Self {
+        Self {
+            ambient_brdf: self.ambient_brdf.tinted(color),
+            diffuse_brdf: self.diffuse_brdf.tinted(color),
+            specular_brdf: self.specular_brdf,
+        }
+    }
+}
+
+/// Only the diffuse lobe takes the texture, highlights and the mirror keep their own color.
+impl Tint for Reflective {
+    fn tinted(&self, color: &Color) -> Self {
+        Self::new(
+            self.ambient_brdf.tinted(color),
+            self.diffuse_brdf.tinted(color),
+            self.specular_brdf,
+            self.reflective_brdf,
+        )
+    }
+}
+
+/// A dielectric has no diffuse lobe, the texture filters the transmitted light.
+impl Tint for Dielectric {
+    fn tinted(&self, color: &Color) -> Self {
+        Self::new(self.ior, self.cf.component_mul(color), self.specular_brdf)
+    }
+}
+
+/// A material with its surface color read from an image texture.
+///
+/// The material is tinted by the texture color at each hit point, which repeats outside
+/// texture coordinates [0, 1]². Sampling densities do not depend on the color and are
+/// left to the material.
+pub struct Textured<M: Material + Tint> {
+    pub material: M,
+    texture: Arc<ImageTexture>,
+}
+
+impl<M: Material + Tint> Textured<M> {
+    #[must_use]
+    pub const fn new(material: M, texture: Arc<ImageTexture>) -> Self {
+        Self { material, texture }
+    }
+
+    /// Returns the material tinted by the texture's color at texture coordinates `texcoords`.
+    fn at(&self, texcoords: Point2<f64>) -> M {
+        self.material.tinted(&self.texture.sample(texcoords.map(|c| c.rem_euclid(1.0))))
+    }
+}
+
+impl<M: Material + Tint> Material for Textured<M> {
+    fn shade(&self, hit: &Hit) -> Color {
+        self.at(hit.texcoords).shade(hit)
+    }
+
+    fn ambient(&self) -> Color {
+        self.material.ambient()
+    }
+
+    fn albedo(&self) -> Color {
+        self.material.albedo()
+    }
+
+    fn albedo_at(&self, texcoords: Point2<f64>) -> Color {
+        self.at(texcoords).albedo()
+    }
+
+    fn diffuse(&self, hit: &Hit, wi: &Vec3) -> Color {
+        self.at(hit.texcoords).diffuse(hit, wi)
+    }
+
+    fn specular(&self, hit: &Hit, wi: &Vec3) -> Color {
+        self.material.specular(hit, wi)
+    }
+
+    fn reflective(&self, hit: &Hit) -> Color {
+        self.at(hit.texcoords).reflective(hit)
+    }
+
+    fn sample_wi(&self, hit: &Hit, u: Point2<f64>) -> Option<Vec3> {
+        self.material.sample_wi(hit, u)
+    }
+
+    fn pdf(&self, hit: &Hit, wi: &Vec3) -> f64 {
+        self.material.pdf(hit, wi)
+    }
+}
@@ -0,0 +1,98 @@
+//! Keyframed rigid motion for motion blur.
+//!
+//! A [`Motion`] maps a ray time to a rigid transform. Translations are
+//! interpolated linearly and rotations spherically between keyframes,
+//! and the first and last keyframes hold before and after the animation.
+
+use nalgebra::{Isometry3, Point3, Translation3};
+
+use crate::{aabb::Aabb, config::motion::BOUNDS_STEPS, model::Vec3};
+
+/// A rigid transform at a point in time.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    /// Time of the keyframe, in the same units as the camera shutter
+    pub time: f64,
+    /// Transform from object space to world space
+    pub transform: Isometry3<f64>,
+}
+
+/// A keyframed rigid motion.
+#[derive(Debug, Clone)]
+pub struct Motion {
+    /// Keyframes sorted by time, never empty
+    keyframes: Vec<Keyframe>,
+}
+
+impl Motion {
+    /// Creates a motion from keyframes, in any order.
+    ///
+    /// Without keyframes the motion is the identity transform.
+    #[must_use]
+    pub fn new(mut keyframes: Vec<Keyframe>) -> Self {
+        if keyframes.is_empty() {
+            keyframes.push(Keyframe { time: 0.0, transform: Isometry3::identity() });
+        }
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { keyframes }
+    }
+
+    /// Creates a linear motion by `offset` between time 0 and time 1.
+    #[must_use]
+    pub fn linear(offset: Vec3) -> Self {
+        Self::new(vec![
+            Keyframe { time: 0.0, transform: Isometry3::identity() },
+            Keyframe { time: 1.0, transform: Translation3::from(offset).into() },
+        ])
+    }
+
+    /// Returns whether the motion has a single transform.
+    #[must_use]
+    pub fn is_static(&self) -> bool {
+        self.keyframes.len() == 1
+    }
+
+    /// Returns the interpolated transform at `time`.
+    #[must_use]
+    pub fn at(&self, time: f64) -> Isometry3<f64> {
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return self.keyframes[0].transform;
+        }
+        if next == self.keyframes.len() {
+            return self.keyframes[next - 1].transform;
+        }
+        let (a, b) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        let t = (time - a.time) / (b.time - a.time);
+        a.transform.lerp_slerp(&b.transform, t)
+    }
+
+    /// Returns a box containing `aabb` at every point of the motion.
+    ///
+    /// Each segment between keyframes is sampled at [`BOUNDS_STEPS`] times,
+    /// so rotating objects are bounded along their arc and not only at the keyframes.
+    #[must_use]
+    pub fn bounds(&self, aabb: &Aabb) -> Aabb {
+        let mut bounds = transform_aabb(aabb, &self.keyframes[0].transform);
+        for pair in self.keyframes.windows(2) {
+            for step in 1..=BOUNDS_STEPS {
+                let t = f64::from(step) / f64::from(BOUNDS_STEPS);
+                let time = (pair[1].time - pair[0].time).mul_add(t, pair[0].time);
+                bounds = Aabb::get_surrounding_aabb(&bounds, &transform_aabb(aabb, &self.at(time)));
+            }
+        }
+        bounds
+    }
+}
+
+/// Returns the box containing the eight transformed corners of `aabb`.
+fn transform_aabb(aabb: &Aabb, transform: &Isometry3<f64>) -> Aabb {
+    let corner = |i: u8| {
+        let pick = |axis: usize| if i & (1 << axis) == 0 { aabb.min[axis] } else { aabb.max[axis] };
+        transform * Point3::new(pick(0), pick(1), pick(2))
+    };
+    (1..8).fold(Aabb::new(corner(0), corner(0)), |bounds, i| {
+        let p = corner(i);
+        Aabb::get_surrounding_aabb(&bounds, &Aabb::new(p, p))
+    })
+}
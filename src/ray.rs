@@ -3,7 +3,7 @@
 //! This module defines the core ray structure and hit record types
 //! used throughout the ray tracing process.
 
-use nalgebra::Point3;
+use nalgebra::{Point2, Point3};
 
 use crate::{material::Material, model::Vec3, renderer::Renderer};
 
@@ -16,6 +16,8 @@ pub struct Ray {
     pub origin: Point3<f64>,
     /// The normalized direction vector
     pub dir: Vec3,
+    /// The time within the camera shutter interval the ray was cast at
+    pub time: f64,
 }
 
 impl Ray {
@@ -26,7 +28,17 @@ impl Ray {
     /// * `dir` - The direction vector (should be normalized)
     #[must_use]
     pub fn new(origin: Point3<f64>, dir: Vec3) -> Self {
-        Self { origin, dir }
+        Self { origin, dir, time: 0.0 }
+    }
+
+    /// Returns the ray cast at `time` instead.
+    ///
+    /// Secondary rays take the time of the ray that spawned them,
+    /// so moving objects are seen in the same position along a path.
+    #[must_use]
+    pub const fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
     }
 
     /// Computes a point along the ray at the specified distance.
@@ -53,6 +65,16 @@ pub struct HitRecord<'a> {
     pub hit_point: Point3<f64>,
     /// Surface normal at the hit point (normalized)
     pub normal: Vec3,
+    /// Surface coordinates of the hit point:
+    /// barycentric `(u, v)` for triangles, longitude and latitude in [0, 1] for spheres
+    pub uv: Point2<f64>,
+    /// Texture coordinates of the hit point, the surface coordinates
+    /// unless the object carries its own
+    pub texcoords: Point2<f64>,
+    /// Derivatives of the hit point along the texture coordinates `u` and `v`,
+    /// which span the tangent plane
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
     /// Reference to the material at the hit point
     pub material: &'a dyn Material,
 }
@@ -61,13 +83,22 @@ pub struct HitRecord<'a> {
 ///
 /// Contains all necessary data for computing the color contribution
 /// at an intersection point, including recursive ray tracing context.
+#[derive(Clone, Copy)]
 pub struct Hit<'a> {
     /// The ray that caused this hit
     pub ray: &'a Ray,
     /// The 3D point where the ray intersects the object
     pub hit_point: Point3<f64>,
-    /// Surface normal at the hit point (normalized)
+    /// Surface normal at the hit point (normalized), facing the ray's origin
     pub normal: Vec3,
+    /// Whether the ray hit the side of the surface its geometric normal points to,
+    /// the outside of closed objects
+    pub front_face: bool,
+    /// Texture coordinates of the hit point, see [`HitRecord::texcoords`]
+    pub texcoords: Point2<f64>,
+    /// Derivatives of the hit point along the texture coordinates
+    pub dpdu: Vec3,
+    pub dpdv: Vec3,
     /// Reference to the renderer for recursive ray tracing
     pub renderer: &'a Renderer,
     /// Current recursion depth for ray bounces
@@ -1,15 +1,68 @@
+use std::{
+    cell::Cell,
+    sync::{Mutex, PoisonError},
+};
+
 use nalgebra::Point2;
 use rayon::prelude::*;
+use serde::Serialize;
 
 use crate::{
-    args::Args,
+    args::{ArgTracer, Args},
+    camera::Shutter,
     color::Color,
     config::render::{DEFAULT_MAX_DEPTH, PREVIEW_MAX_DEPTH, PREVIEW_SAMPLES},
+    denoiser::FeatureBuffers,
+    light::LightSampler,
+    model::Vec3,
     ray::{Hit, Ray},
     sampler::Sampler,
     scene::Scene,
+    stats::{self, Counter, Stats},
+    tracer,
 };
 
+/// A rectangular window of pixels, from `(x0, y0)` to `(x1, y1)` exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Region {
+    pub x0: u32,
+    pub y0: u32,
+    pub x1: u32,
+    pub y1: u32,
+}
+
+impl Region {
+    /// Creates a region covering a whole `width` x `height` image
+    #[must_use]
+    pub const fn full(width: u32, height: u32) -> Self {
+        Self { x0: 0, y0: 0, x1: width, y1: height }
+    }
+
+    /// Returns the width of the region in pixels
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.x1.saturating_sub(self.x0)
+    }
+
+    /// Returns the height of the region in pixels
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.y1.saturating_sub(self.y0)
+    }
+
+    /// Returns whether the region contains no pixels
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    /// Returns the image coordinates of the pixel with row-major index `n` within the region
+    #[must_use]
+    pub const fn pixel(&self, n: u32) -> (u32, u32) {
+        (self.x0 + n % self.width(), self.y0 + n / self.width())
+    }
+}
+
 /// The main rendering engine that traces rays through a scene
 pub struct Renderer {
     /// The scene to render
@@ -18,46 +71,187 @@ pub struct Renderer {
     pub sampler: Sampler,
     /// Maximum recursion depth for ray bounces
     max_depth: u8,
+    /// Tracer used for primary rays
+    tracer: ArgTracer,
+    /// Counters aggregated from all worker threads
+    stats: Mutex<Stats>,
+    /// Window of the image that is traced
+    region: Region,
+    /// Interval camera rays are cast in
+    shutter: Shutter,
+    /// Picks the lights sampled at each shading point
+    pub light_sampler: LightSampler,
 }
 
 impl Renderer {
     /// Creates a new renderer with the given scene and configuration
     #[must_use]
     pub fn new(scene: Box<dyn Scene>, args: &Args) -> Self {
+        let (width, height) = (scene.view_width(), scene.view_height());
+        let region =
+            args.crop.map_or(Region::full(width, height), |crop| crop.region(width, height));
+        let light_sampler =
+            LightSampler::new(scene.lights(), args.light_selection, args.light_samples);
         Self {
             scene,
             sampler: Sampler::new(if args.preview { PREVIEW_SAMPLES } else { args.samples }),
             max_depth: if args.preview { PREVIEW_MAX_DEPTH } else { DEFAULT_MAX_DEPTH },
+            tracer: args.tracer,
+            stats: Mutex::new(Stats::default()),
+            region,
+            shutter: Shutter::new(args.shutter_open, args.shutter_close),
+            light_sampler,
         }
     }
 
+    /// Returns the window of the image that is traced
+    #[must_use]
+    pub const fn region(&self) -> Region {
+        self.region
+    }
+
+    /// Returns the interval camera rays are cast in
+    #[must_use]
+    pub const fn shutter(&self) -> Shutter {
+        self.shutter
+    }
+
     /// Returns the maximum ray tracing depth
     #[must_use]
     pub const fn max_depth(&self) -> u8 {
         self.max_depth
     }
 
-    /// Renders the scene and returns a vector of colors for each pixel
+    /// Returns the ray and intersection counters accumulated by all renders so far
     #[must_use]
-    pub fn render(&self) -> Vec<Color> {
+    pub fn stats(&self) -> Stats {
+        *self.stats.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Moves the counters of the current thread into the renderer's totals
+    fn collect_stats(&self) {
+        *self.stats.lock().unwrap_or_else(PoisonError::into_inner) += stats::take();
+    }
+
+    /// Maps the pixels of the crop window in parallel, returning the values in row-major order.
+    ///
+    /// Each row is mapped on one thread, whose counters are merged into the renderer's totals
+    /// once per row, so the threads rarely wait for each other.
+    fn map_pixels<T: Send>(&self, f: impl Fn(u32) -> T + Sync) -> Vec<T> {
+        let width = self.region.width();
+        (0..self.region.height())
+            .into_par_iter()
+            .flat_map_iter(|row| {
+                let values: Vec<T> = (row * width..(row + 1) * width).map(&f).collect();
+                self.collect_stats();
+                values
+            })
+            .collect()
+    }
+
+    /// Returns the view plane point of the pixel at `(x, y)` of the full image.
+    ///
+    /// Points only depend on the pixel position in the full image,
+    /// so a cropped render matches the same window of a full render.
+    fn view_plane_point(&self, (x, y): (u32, u32)) -> Point2<f64> {
         let width = self.scene.view_width();
         let height = self.scene.view_height();
         let pixel_size = self.scene.camera().setting().pixel_size;
+        let i = pixel_size * (f64::from(x) - f64::from(width) / 2.0);
+        let j = pixel_size * (f64::from(y) - f64::from(height) / 2.0);
+        Point2::new(i, j)
+    }
 
-        let vec = (0..(width * height))
-            .into_par_iter()
-            .flat_map_iter(|n| {
-                let i = pixel_size * (f64::from(n % width) - f64::from(width) / 2.0);
-                let j = pixel_size * (f64::from(n / width) - f64::from(height) / 2.0);
-                let origin = Point2::new(i, j);
-                self.scene.camera().get_rays(origin, &self.sampler).into_iter()
+    /// Returns a function spreading the successive camera rays of one pixel over the
+    /// shutter interval.
+    ///
+    /// Each view of the pixel uses the same times, in the same order.
+    fn ray_timer(&self) -> impl Fn(&Ray) -> Ray {
+        let times = self.shutter.times(&self.sampler);
+        let next = Cell::new(0);
+        move |ray| {
+            let time = times[next.get() % times.len()];
+            next.set(next.get() + 1);
+            Ray::new(ray.origin, ray.dir).with_time(time)
+        }
+    }
+
+    /// Generates the camera rays for the pixel at `(x, y)` of the full image,
+    /// spread over the shutter interval
+    fn camera_rays(&self, pixel: (u32, u32)) -> Vec<Ray> {
+        let rays = self.scene.camera().get_rays(self.view_plane_point(pixel), &self.sampler);
+        rays.iter().map(self.ray_timer()).collect()
+    }
+
+    /// Renders the scene and returns a vector of colors for each pixel.
+    ///
+    /// Only the pixels of the crop window are traced, in row-major order.
+    #[must_use]
+    pub fn render(&self) -> Vec<Color> {
+        let region = self.region;
+
+        let mut pixels = self.map_pixels(|n| {
+            let origin = self.view_plane_point(region.pixel(n));
+            let timed = self.ray_timer();
+            self.scene.camera().render_pixel(origin, &self.sampler, &|ray| {
+                stats::increment(Counter::CameraRays);
+                let ray = timed(ray);
+                match self.tracer {
+                    ArgTracer::Whitted => self.trace(&ray, 0),
+                    debug => tracer::trace(self, &ray, debug),
+                }
             })
-            .map(|ray| self.trace(&ray, 0))
-            .collect::<Vec<_>>();
+        });
 
-        vec.chunks(self.sampler.count().into())
-            .map(|chunks| chunks.iter().sum::<Color>() / f64::from(self.sampler.count()))
-            .collect()
+        tracer::normalize(self.tracer, &mut pixels);
+        pixels
+    }
+
+    /// Renders the denoiser feature buffers (albedo, normal and depth).
+    ///
+    /// Each pixel averages the first hit of all its camera rays,
+    /// so the features are antialiased the same way as the color image.
+    /// Like `render`, only the crop window is traced.
+    /// The rays of this pass are not counted in [`Renderer::stats`].
+    #[must_use]
+    pub fn render_features(&self) -> FeatureBuffers {
+        let region = self.region;
+        let (width, height) = (region.width(), region.height());
+
+        let (albedo, (normal, depth)): (Vec<_>, (Vec<_>, Vec<_>)) = self
+            .map_pixels(|n| {
+                let rays = self.camera_rays(region.pixel(n));
+                let count = f64::from(self.sampler.count());
+
+                let mut albedo = Color::zeros();
+                let mut normal = Vec3::zeros();
+                let mut depth = 0.0;
+                for ray in &rays {
+                    if let Some(record) = self.scene.intersects(ray, 0.0, f64::INFINITY) {
+                        albedo += record.material.albedo_at(record.texcoords);
+                        normal += record.normal * record.normal.dot(&-ray.dir).signum();
+                        depth += record.dist;
+                    }
+                }
+                stats::reset();
+                (albedo / count, (normal.try_normalize(0.0).unwrap_or(normal), depth / count))
+            })
+            .into_iter()
+            .unzip();
+
+        FeatureBuffers { width, height, albedo, normal, depth }
+    }
+
+    /// Places the pixels of the crop window into a full-size black image
+    #[must_use]
+    pub fn expand_to_full_frame(&self, pixels: &[Color]) -> Vec<Color> {
+        let width = self.scene.view_width();
+        let mut frame = vec![Color::zeros(); (width * self.scene.view_height()) as usize];
+        for (n, color) in (0..).zip(pixels) {
+            let (x, y) = self.region.pixel(n);
+            frame[(y * width + x) as usize] = *color;
+        }
+        frame
     }
 
     /// Traces a ray through the scene and returns the resulting color
@@ -73,7 +267,9 @@ impl Renderer {
         if depth > self.max_depth {
             return Color::zeros();
         }
-        self.scene.intersects(ray, 0.0, f64::INFINITY).map_or_else(Color::zeros, |record| {
+        let background =
+            || self.scene.environment().map_or_else(Color::zeros, |e| e.radiance_along(&ray.dir));
+        self.scene.intersects(ray, 0.0, f64::INFINITY).map_or_else(background, |record| {
             let wo = -ray.dir;
             // revert normal if we hit the inside surface
             let adjusted_normal = record.normal * record.normal.dot(&wo).signum();
@@ -82,6 +278,10 @@ impl Renderer {
                 hit_point: record.hit_point,
                 material: record.material,
                 normal: adjusted_normal,
+                front_face: record.normal.dot(&wo) > 0.0,
+                texcoords: record.texcoords,
+                dpdu: record.dpdu,
+                dpdv: record.dpdv,
                 renderer: self,
                 depth,
             };
@@ -0,0 +1,153 @@
+//! Machine-readable render reports.
+//!
+//! A report captures the effective configuration, phase timings,
+//! ray statistics and output files of a single run as JSON.
+//! Fields are only ever added to the schema; bump [`SCHEMA_VERSION`]
+//! when an existing field changes meaning or is removed.
+
+use std::{fs::File, io::BufWriter, path::Path};
+
+use serde::Serialize;
+
+use crate::{
+    args::{ArgCamera, ArgTracer, Args, Crop, FrameRange},
+    camera::{Interpolation, Shutter, StereoLayout},
+    error::Result,
+    light::LightSelection,
+    renderer::Renderer,
+    scene::{SceneFactory, SceneType},
+    stats::Stats,
+};
+
+/// Version of the report schema
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A complete render report.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    /// Version of the report schema
+    pub schema_version: u32,
+    /// Effective render configuration
+    pub config: ReportConfig,
+    /// Wall clock time spent in each phase
+    pub timings: Timings,
+    /// Ray and intersection counters of the render phase
+    pub stats: Stats,
+    /// Paths of the files written by the run
+    pub outputs: Vec<String>,
+}
+
+/// The effective configuration after applying preview mode and defaults.
+#[derive(Debug, Serialize)]
+pub struct ReportConfig {
+    /// Image width in pixels
+    pub width: u32,
+    /// Image height in pixels
+    pub height: u32,
+    /// Camera model
+    pub camera: ArgCamera,
+    /// Sampler settings
+    pub sampler: SamplerConfig,
+    /// Maximum ray recursion depth
+    pub max_depth: u8,
+    /// Tracer used for primary rays
+    pub tracer: ArgTracer,
+    /// Rendered scene
+    pub scene: SceneType,
+    /// Whether preview mode was requested
+    pub preview: bool,
+    /// Whether the image was denoised
+    pub denoise: bool,
+    /// Rendered window of the image, if cropped
+    pub crop: Option<Crop>,
+    /// Whether a cropped render was saved at full size
+    pub crop_full_frame: bool,
+    /// Interval the camera shutter was open for
+    pub shutter: Shutter,
+    /// Layout of the stereo pair, if rendered in stereo
+    pub stereo: Option<StereoLayout>,
+    /// Rendered frames, if an animation was rendered
+    pub frames: Option<FrameRange>,
+    /// Interpolation of the camera path, if the camera was animated
+    pub camera_interpolation: Option<Interpolation>,
+    /// How lights were picked at shading points
+    pub light_selection: LightSelection,
+    /// Lights picked per shading point, if not all lights were sampled
+    pub light_samples: Option<u8>,
+}
+
+/// Sampler settings.
+#[derive(Debug, Serialize)]
+pub struct SamplerConfig {
+    /// Sampling pattern
+    pub kind: &'static str,
+    /// Samples per pixel
+    pub samples_per_pixel: u8,
+}
+
+/// Phase timings in seconds.
+#[derive(Debug, Default, Serialize)]
+pub struct Timings {
+    /// Asset loading and scene setup, excluding the BVH build
+    pub scene_load: f64,
+    /// BVH construction
+    pub bvh_build: f64,
+    /// Ray tracing
+    pub render: f64,
+    /// Denoising, zero when disabled
+    pub denoise: f64,
+    /// Whole run, including writing the outputs
+    pub total: f64,
+}
+
+impl Report {
+    /// Creates a report for a finished render.
+    ///
+    /// # Arguments
+    /// * `args` - Command-line arguments of the run
+    /// * `renderer` - The renderer used, for its effective settings
+    /// * `timings` - Phase timings
+    /// * `stats` - Counters of the render phase
+    #[must_use]
+    pub fn new(args: &Args, renderer: &Renderer, timings: Timings, stats: Stats) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            config: ReportConfig {
+                width: renderer.scene.view_width(),
+                height: renderer.scene.view_height(),
+                camera: args.camera.clone(),
+                sampler: SamplerConfig {
+                    kind: "jittered",
+                    samples_per_pixel: renderer.sampler.count(),
+                },
+                max_depth: renderer.max_depth(),
+                tracer: args.tracer,
+                scene: SceneFactory::scene_type(args),
+                preview: args.preview,
+                denoise: args.denoise,
+                crop: args.crop,
+                crop_full_frame: args.crop_full_frame,
+                shutter: renderer.shutter(),
+                stereo: args.stereo,
+                frames: args.frames,
+                camera_interpolation: args.camera_path.as_ref().map(|_| args.camera_interpolation),
+                light_selection: args.light_selection,
+                light_samples: (args.light_selection != LightSelection::All)
+                    .then_some(args.light_samples),
+            },
+            timings,
+            stats,
+            outputs: vec![],
+        }
+    }
+
+    /// Writes the report as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created or written
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self).map_err(std::io::Error::from)?;
+        Ok(())
+    }
+}
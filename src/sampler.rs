@@ -1,12 +1,13 @@
 //! Sampling strategies for antialiasing and Monte Carlo integration.
 
+use std::cell::RefCell;
 use std::f64::consts::FRAC_PI_4;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use nalgebra::{Point2, Point3};
 use num_integer::Roots;
-use rand::{RngExt, SeedableRng, distr::StandardUniform, rngs::SmallRng};
+use rand::{RngExt, SeedableRng, distr::StandardUniform, rngs::SmallRng, seq::SliceRandom};
 
 use crate::model::Vec3;
 
@@ -19,6 +20,11 @@ fn make_rng() -> SmallRng {
     SmallRng::seed_from_u64(time_seed ^ counter_seed)
 }
 
+thread_local! {
+    /// Generator of each thread, seeded once and reused by the light picks of every shading point
+    static RNG: RefCell<SmallRng> = RefCell::new(make_rng());
+}
+
 /// Generates sample points for various rendering techniques including
 /// antialiasing, area lighting, and Monte Carlo integration.
 pub struct Sampler {
@@ -78,6 +84,37 @@ impl Sampler {
         self.samples.iter().skip(skip).take(self.count().into()).copied().collect()
     }
 
+    /// Returns one time in [0, 1) per sample, stratified and in random order.
+    ///
+    /// The order is shuffled so times are not correlated with the pixel samples.
+    /// A single sample uses the center of the interval.
+    #[must_use]
+    pub fn times(&self) -> Vec<f64> {
+        let n = self.count();
+        if n == 1 {
+            return vec![0.5];
+        }
+        Self::stratified(n)
+    }
+
+    /// Returns `n` random numbers in [0, 1), one in each of `n` equal intervals,
+    /// in random order.
+    #[must_use]
+    pub fn stratified(n: u8) -> Vec<f64> {
+        let mut rng = make_rng();
+        let mut numbers: Vec<f64> = (0..n)
+            .map(|i| (f64::from(i) + rng.sample::<f64, _>(StandardUniform)) / f64::from(n))
+            .collect();
+        numbers.shuffle(&mut rng);
+        numbers
+    }
+
+    /// Returns a random number in [0, 1) from the generator of the calling thread.
+    #[must_use]
+    pub fn uniform() -> f64 {
+        RNG.with(|rng| rng.borrow_mut().sample(StandardUniform))
+    }
+
     /// Returns sample points in unit square as Point2 coordinates
     pub fn square(&self) -> impl Iterator<Item = Point2<f64>> {
         self.unit_square().into_iter().map(|(x, y)| Point2::new(x, y))
@@ -104,32 +141,41 @@ impl Sampler {
     /// Maps unit square samples to hemisphere using cosine-weighted distribution
     /// for importance sampling in global illumination
     pub fn hemisphere(&self) -> impl Iterator<Item = Vec3> {
-        self.square().map(|p| {
-            let e = 1.0;
-            let phi = 2.0 * std::f64::consts::PI * p.x;
-            let cos_phi = phi.cos();
-            let sin_phi = phi.sin();
-            let cos_theta = (1.0 - p.y).powf((e + 1.0_f64).recip());
-            let sin_theta = cos_theta.mul_add(-cos_theta, 1.0).sqrt();
-            Vec3::new(sin_theta * cos_phi, sin_theta * sin_phi, cos_theta)
-        })
+        self.square().map(|p| Self::cosine_power(p, 1.0))
+    }
+
+    /// Maps a point in the unit square to the hemisphere around +z,
+    /// distributed with a density proportional to `cos^e θ`
+    #[must_use]
+    pub fn cosine_power(p: Point2<f64>, e: f64) -> Vec3 {
+        let phi = 2.0 * std::f64::consts::PI * p.x;
+        let cos_phi = phi.cos();
+        let sin_phi = phi.sin();
+        let cos_theta = (1.0 - p.y).powf((e + 1.0_f64).recip());
+        let sin_theta = cos_theta.mul_add(-cos_theta, 1.0).sqrt();
+        Vec3::new(sin_theta * cos_phi, sin_theta * sin_phi, cos_theta)
     }
 
     /// Maps unit square samples to unit disk using concentric mapping
     /// for depth of field and area light sampling  
     pub fn disk(&self) -> impl Iterator<Item = (Point2<f64>, Point2<f64>)> {
-        self.square().map(|p| {
-            let x = 2.0f64.mul_add(p.x, -1.0);
-            let y = 2.0f64.mul_add(p.y, -1.0);
-            let (r, phi) = if x > -y {
-                if x > y { (x, y / x) } else { (y, 2.0 - x / y) }
-            } else if x < y {
-                (-x, 4.0 + y / x)
-            } else {
-                (-y, if y == 0.0 { 0.0 } else { 6.0 - x / y })
-            };
-            let phi_ = phi * FRAC_PI_4;
-            (p, Point2::new(r * phi_.cos(), r * phi_.sin()))
-        })
+        self.square().map(|p| (p, Self::concentric(p)))
+    }
+
+    /// Maps a point in the unit square to the unit disk with Shirley's concentric mapping,
+    /// which keeps the stratification of the samples
+    #[must_use]
+    pub fn concentric(p: Point2<f64>) -> Point2<f64> {
+        let x = 2.0f64.mul_add(p.x, -1.0);
+        let y = 2.0f64.mul_add(p.y, -1.0);
+        let (r, phi) = if x > -y {
+            if x > y { (x, y / x) } else { (y, 2.0 - x / y) }
+        } else if x < y {
+            (-x, 4.0 + y / x)
+        } else {
+            (-y, if y == 0.0 { 0.0 } else { 6.0 - x / y })
+        };
+        let phi_ = phi * FRAC_PI_4;
+        Point2::new(r * phi_.cos(), r * phi_.sin())
     }
 }
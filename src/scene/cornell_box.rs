@@ -5,49 +5,79 @@
 //! algorithms. It consists of a box with colored walls, light source,
 //! and objects to demonstrate light transport.
 
-use std::sync::Arc;
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use nalgebra::Point2;
 
 use crate::{
     accelerator::Bvh,
-    args::{ArgCamera, Args},
-    asset::Asset,
+    args::{ArgCamera, Args, Pixel, Power},
+    asset::{Asset, AssetOptions},
     brdf::{GlossySpecular, Lambertian, PerfectSpecular},
-    camera::{Camera, Pinhole, Setting, ThinLens},
+    camera::{
+        Aperture, ApertureMask, Camera, CameraKey, Fisheye, Fov, LensSystem, Orthographic, Pinhole,
+        RealisticLens, Setting, Spherical, Stereo, StereoViewing, ThinLens,
+    },
     color::Color,
     config::{
         camera::{
-            DEFAULT_EYE_POSITION, DEFAULT_FOCAL_DISTANCE, DEFAULT_FOCAL_LENGTH,
-            DEFAULT_LENS_RADIUS, DEFAULT_LOOKAT_POSITION,
+            DEFAULT_EYE_POSITION, DEFAULT_FILM_DIAGONAL, DEFAULT_LENS_FILE,
+            DEFAULT_LENS_FOCUS_DISTANCE, DEFAULT_LOOKAT_POSITION,
         },
         geometry::spheres::{
-            LARGE_SPHERE_POSITION, LARGE_SPHERE_RADIUS, SMALL_SPHERE_POSITION, SMALL_SPHERE_RADIUS,
+            LARGE_SPHERE_POSITION, LARGE_SPHERE_RADIUS, SMALL_SPHERE_MOTION, SMALL_SPHERE_POSITION,
+            SMALL_SPHERE_RADIUS,
+        },
+        light::{
+            DIRECTIONAL_LIGHT_DIRECTION, DIRECTIONAL_LIGHT_INTENSITY, POINT_LIGHT_POSITION,
+            SKY_MAP_HEIGHT, SKY_MAP_WIDTH, SPOT_LIGHT_INNER_ANGLE, SPOT_LIGHT_INTENSITY,
+            SPOT_LIGHT_OUTER_ANGLE, SPOT_LIGHT_POSITION, SPOT_LIGHT_TARGET,
         },
         scene::{CORNELL_BOX_ASSET_PATH, CORNELL_BOX_SCALE, DEFAULT_AMBIENT_STRENGTH},
     },
     error::Result,
     geometric_object::{Geometry, Sphere},
-    light::{Ambient, AmbientOcculuder, Light},
+    light::{
+        Ambient, AmbientOcculuder, Directional, Environment, IesProfile, Light, Point, Sky, Spot,
+    },
     material::{Phong, Reflective},
     model::{Pot3, Vec3},
+    motion::Motion,
     ray::{HitRecord, Ray},
+    texture::ImageTexture,
 };
 
 use super::Scene;
 
+/// World units per millimetre, the box is 555 mm wide and scaled to 2 units
+const UNITS_PER_MM: f64 = 2.0 / CORNELL_BOX_SCALE;
+
 /// Classic Cornell Box scene with colored walls and spheres.
 ///
 /// Features:
 /// - Red left wall, green right wall, white other walls
-/// - Area light at the top
+/// - Area light at the top, and optionally directional, point and spot lights
+///   and an environment map or sky
 /// - Two spheres with different materials (reflective and Phong)
 /// - BVH acceleration structure
 pub struct CornellBox {
     pub view_width: u32,
     pub view_height: u32,
     pub camera: Box<dyn Camera>,
+    /// Setting of a single view, which animation keyframes reframe
+    pub camera_setting: Setting,
+    /// Lens, aperture and focus of the camera, kept when keyframes reframe it
+    optics: Optics,
     pub ambient_light: Arc<Ambient>,
     pub lights: Vec<Arc<dyn Light>>,
+    /// Environment lighting the box and seen through its open front
+    pub environment: Option<Arc<Environment>>,
     pub root: Vec<Arc<dyn Geometry>>,
+    pub bvh_build_time: Duration,
 }
 
 impl CornellBox {
@@ -61,15 +91,16 @@ impl CornellBox {
     /// # Scene Setup
     /// 1. Loads the Cornell Box geometry from OBJ file
     /// 2. Adds two spheres with different materials
-    /// 3. Sets up camera (pinhole or thin lens)
+    /// 3. Sets up camera (pinhole or thin lens), optionally as a stereo pair
     /// 4. Configures lighting (ambient + area light)
     /// 5. Builds BVH acceleration structure
     ///
     /// # Errors
     /// Returns an error if the scene assets cannot be loaded
     pub fn new(view_width: u32, view_height: u32, args: &Args) -> Result<Self> {
-        // Load base geometry from OBJ file
-        let mut asset = Asset::new(CORNELL_BOX_ASSET_PATH, CORNELL_BOX_SCALE)?;
+        // Load base geometry from OBJ file, whose ceiling light uses the legacy ambient emission
+        let options = AssetOptions { legacy_ambient_emission: true, ..AssetOptions::default() };
+        let mut asset = Asset::with_options(CORNELL_BOX_ASSET_PATH, CORNELL_BOX_SCALE, &options)?;
 
         // Configure ambient lighting
         let ambient_light =
@@ -79,25 +110,35 @@ impl CornellBox {
         let ambient_occuluder = Arc::new(AmbientOcculuder::new(1.0, Vec3::repeat(1.0)));
         asset.lights.push(ambient_occuluder);
 
-        // Configure camera position and field of view
-        let mut camera_setting = Setting::new(
-            Pot3::new(DEFAULT_EYE_POSITION[0], DEFAULT_EYE_POSITION[1], DEFAULT_EYE_POSITION[2]),
-            Pot3::new(
-                DEFAULT_LOOKAT_POSITION[0],
-                DEFAULT_LOOKAT_POSITION[1],
-                DEFAULT_LOOKAT_POSITION[2],
-            ),
-            DEFAULT_FOCAL_LENGTH,
-        );
-        camera_setting.set_view((view_width, view_height));
+        // Add the lights enabled on the command line
+        asset.lights.extend(Self::optional_lights(args)?);
 
-        // Select camera type based on arguments
-        let camera: Box<dyn Camera> = match args.camera {
-            ArgCamera::Simple => Box::new(Pinhole::new(camera_setting)),
-            ArgCamera::ThinLens => {
-                Box::new(ThinLens::new(camera_setting, DEFAULT_LENS_RADIUS, DEFAULT_FOCAL_DISTANCE))
-            }
+        // Add an environment map or a sky around the box
+        let environment = if args.sky {
+            let sky = Sky::new(args.sun_elevation, args.sun_azimuth, args.turbidity);
+            Some(sky.to_environment(SKY_MAP_WIDTH, SKY_MAP_HEIGHT))
+        } else {
+            args.environment.as_ref().map(|path| Environment::from_file(path)).transpose()?
+        };
+        let environment = environment.map(|environment| {
+            Arc::new(
+                environment
+                    .with_intensity(args.environment_intensity)
+                    .with_rotation(args.environment_rotation),
+            )
+        });
+        if let Some(environment) = &environment {
+            asset.lights.push(Arc::clone(environment) as Arc<dyn Light>);
+        }
+
+        // Configure camera position and field of view
+        let key = CameraKey {
+            frame: 0.0,
+            eye: Pot3::from(DEFAULT_EYE_POSITION),
+            lookat: Pot3::from(DEFAULT_LOOKAT_POSITION),
+            fov: args.fov,
         };
+        let camera_setting = Self::camera_setting(&key, (view_width, view_height), args);
 
         // Add reflective sphere (demonstrates perfect and glossy reflections)
         let ball1_material = Reflective::new(
@@ -120,18 +161,185 @@ impl CornellBox {
             Lambertian::new(0.1, Color::repeat(1.0)),          // Diffuse
             GlossySpecular::new(0.3, 2.0, Color::repeat(1.0)), // Specular with shininess
         );
-        let ball2 = Arc::new(Sphere::new(
+        let mut ball2 = Sphere::new(
             ball2_material,
             SMALL_SPHERE_RADIUS,
             Pot3::new(SMALL_SPHERE_POSITION[0], SMALL_SPHERE_POSITION[1], SMALL_SPHERE_POSITION[2]),
             CORNELL_BOX_SCALE,
-        ));
-        asset.geometries.push(ball2);
+        );
+        // Moving costs time on every intersection, so only move while the shutter is open
+        if args.shutter_close > args.shutter_open {
+            ball2 = ball2.with_motion(Motion::linear(Vec3::from(SMALL_SPHERE_MOTION)));
+        }
+        asset.geometries.push(Arc::new(ball2));
 
         // Build BVH acceleration structure for efficient ray tracing
+        let now = Instant::now();
         let root = vec![Bvh::construct(asset.geometries)];
+        let bvh_build_time = now.elapsed();
+
+        let optics = Optics::new(&camera_setting, args, &root)?;
+        let camera = Self::build_camera(camera_setting.clone(), args, &optics)?;
+        // Stereo cameras may produce a wider image than a single view
+        let view_width = camera.setting().view_width;
+
+        Ok(Self {
+            view_width,
+            view_height,
+            camera,
+            camera_setting,
+            optics,
+            ambient_light,
+            lights: asset.lights,
+            environment,
+            root,
+            bvh_build_time,
+        })
+    }
+
+    /// Creates the directional, point and spot lights enabled by `args`.
+    ///
+    /// # Errors
+    /// Returns an error if the spot light's gobo or an IES profile cannot be loaded
+    fn optional_lights(args: &Args) -> Result<Vec<Arc<dyn Light>>> {
+        let mut lights: Vec<Arc<dyn Light>> = vec![];
+
+        // Add a sun shining in through the open front of the box
+        if args.directional_light {
+            let sun = Directional::new(
+                DIRECTIONAL_LIGHT_INTENSITY,
+                Color::repeat(1.0),
+                Vec3::from(DIRECTIONAL_LIGHT_DIRECTION),
+            )
+            .with_angular_diameter(args.directional_light_diameter);
+            lights.push(Arc::new(sun));
+        }
+
+        // Add a point light below the ceiling, in physically based units
+        if let Some(power) = args.point_light {
+            let location = Pot3::from(POINT_LIGHT_POSITION);
+            let point = match power {
+                Power::Watts(watts) => Point::from_watts(watts, Color::repeat(1.0), location),
+                Power::Lumens(lumens) => Point::from_lumens(lumens, Color::repeat(1.0), location),
+            };
+            let point = point
+                .with_falloff(args.point_light_falloff)
+                .with_radius(args.point_light_radius)
+                .with_metres_per_unit(1.0 / (UNITS_PER_MM * 1000.0));
+            let point = match &args.point_light_ies {
+                Some(path) => point.with_ies(Arc::new(IesProfile::from_file(path)?)),
+                None => point,
+            };
+            lights.push(Arc::new(point));
+        }
+
+        // Add a spot light lighting up the large sphere, optionally through a gobo
+        if args.spot_light {
+            let mut spot = Spot::new(
+                SPOT_LIGHT_INTENSITY,
+                Color::repeat(1.0),
+                Pot3::from(SPOT_LIGHT_POSITION),
+                Pot3::from(SPOT_LIGHT_TARGET),
+                SPOT_LIGHT_INNER_ANGLE,
+                SPOT_LIGHT_OUTER_ANGLE,
+            )
+            .with_metres_per_unit(1.0 / (UNITS_PER_MM * 1000.0));
+            if let Some(path) = &args.gobo {
+                spot = spot.with_gobo(Arc::new(ImageTexture::from_file(path)?));
+            }
+            if let Some(path) = &args.spot_light_ies {
+                spot = spot.with_ies(Arc::new(IesProfile::from_file(path)?));
+            }
+            lights.push(Arc::new(spot));
+        }
+
+        Ok(lights)
+    }
+
+    /// Creates the setting of a single view framed by `key`.
+    fn camera_setting(key: &CameraKey, view: (u32, u32), args: &Args) -> Setting {
+        let fov =
+            if args.horizontal_fov { Fov::Horizontal(key.fov) } else { Fov::Vertical(key.fov) };
+        let mut setting = Setting::new(key.eye, key.lookat)
+            .with_fov(fov)
+            .with_roll(args.roll)
+            .with_zoom(args.zoom);
+        setting.set_view(view);
+        setting
+    }
+
+    /// Creates the camera selected by the arguments, optionally as a stereo pair.
+    ///
+    /// # Errors
+    /// Returns an error if the camera cannot be created, see [`Stereo::new`]
+    fn build_camera(
+        camera_setting: Setting,
+        args: &Args,
+        optics: &Optics,
+    ) -> Result<Box<dyn Camera>> {
+        Ok(match args.stereo {
+            Some(layout) => {
+                let viewing = if args.stereo_transverse {
+                    StereoViewing::Transverse
+                } else {
+                    StereoViewing::Parallel
+                };
+                Box::new(Stereo::new(
+                    camera_setting,
+                    args.interocular_distance,
+                    layout,
+                    viewing,
+                    |setting| Ok(Self::create_camera(setting, args, optics)),
+                )?)
+            }
+            None => Self::create_camera(camera_setting, args, optics),
+        })
+    }
 
-        Ok(Self { view_width, view_height, camera, ambient_light, lights: asset.lights, root })
+    /// Returns the view plane point at the center of a pixel of the output image.
+    ///
+    /// Matches the renderer's mapping for the center sample,
+    /// with `x` mirrored like the saved image, see [`crate::args::Crop::region`].
+    fn view_plane_point(setting: &Setting, pixel: Pixel) -> Point2<f64> {
+        let (width, height) = (f64::from(setting.view_width), f64::from(setting.view_height));
+        let x = (width - 1.0 - f64::from(pixel.x)).clamp(0.0, width - 1.0);
+        let y = f64::from(pixel.y).min(height - 1.0);
+        Point2::new(x - width / 2.0 - 0.5, y - height / 2.0 - 0.5) * setting.pixel_size
+    }
+
+    /// Creates the camera selected by the arguments.
+    ///
+    /// # Arguments
+    /// * `camera_setting` - Setting of the camera
+    /// * `args` - Command-line arguments selecting the camera
+    /// * `optics` - Aperture, lens and focus distance loaded for the camera
+    fn create_camera(camera_setting: Setting, args: &Args, optics: &Optics) -> Box<dyn Camera> {
+        match args.camera {
+            ArgCamera::Simple => Box::new(Pinhole::new(camera_setting)),
+            ArgCamera::ThinLens => Box::new(
+                ThinLens::new(
+                    camera_setting,
+                    args.focal_length * UNITS_PER_MM,
+                    args.f_number,
+                    optics.focus_distance,
+                )
+                .with_aperture(optics.aperture.clone()),
+            ),
+            ArgCamera::Orthographic => {
+                Box::new(Orthographic::new(camera_setting, args.orthographic_width))
+            }
+            ArgCamera::Fisheye => Box::new(Fisheye::new(camera_setting, args.fisheye_fov)),
+            ArgCamera::Spherical => Box::new(Spherical::new(
+                camera_setting,
+                args.spherical_horizontal_fov,
+                args.spherical_vertical_fov,
+            )),
+            ArgCamera::RealisticLens => Box::new(RealisticLens::new(
+                camera_setting,
+                optics.lens.clone().expect("the realistic lens camera has a lens"),
+                DEFAULT_FILM_DIAGONAL * UNITS_PER_MM,
+            )),
+        }
     }
 
     /// Implementation moved from trait method for performance.
@@ -148,6 +356,64 @@ impl CornellBox {
     }
 }
 
+/// Camera parts that are loaded or measured once and kept when the camera is reframed
+struct Optics {
+    /// Aperture of the thin lens camera
+    aperture: Aperture,
+    /// Focused lens of the realistic lens camera, only loaded for that camera
+    lens: Option<LensSystem>,
+    /// Distance the thin lens camera focuses at
+    focus_distance: f64,
+}
+
+impl Optics {
+    /// Loads the aperture mask and lens selected by the arguments and focuses the thin lens.
+    ///
+    /// Without a focus distance the thin lens focuses on the first hit in `root`
+    /// through the autofocus pixel of the initial view, or on the look-at point
+    /// if nothing is hit.
+    ///
+    /// # Errors
+    /// Returns an error if the aperture mask of the thin lens camera cannot be loaded,
+    /// or the lens of the realistic lens camera cannot be loaded or focused
+    fn new(camera_setting: &Setting, args: &Args, root: &[Arc<dyn Geometry>]) -> Result<Self> {
+        // Only the thin lens has an aperture, other cameras skip loading its mask
+        let aperture = match (&args.aperture_mask, args.aperture_blades) {
+            (Some(path), _) if matches!(args.camera, ArgCamera::ThinLens) => {
+                Aperture::Mask(Arc::new(ApertureMask::from_file(path)?))
+            }
+            (_, Some(blades)) => {
+                Aperture::Polygon { blades, rotation: args.aperture_rotation.to_radians() }
+            }
+            _ => Aperture::Disk,
+        };
+
+        let lens = if matches!(args.camera, ArgCamera::RealisticLens) {
+            let path = args.lens_file.as_deref().unwrap_or(Path::new(DEFAULT_LENS_FILE));
+            let mut lens = LensSystem::from_file(path, UNITS_PER_MM)?;
+            lens.focus(DEFAULT_LENS_FOCUS_DISTANCE)?;
+            Some(lens)
+        } else {
+            None
+        };
+
+        let (view_width, view_height) = (camera_setting.view_width, camera_setting.view_height);
+        let focus_distance = args.focus_distance.unwrap_or_else(|| {
+            let pixel = args.autofocus.unwrap_or(Pixel { x: view_width / 2, y: view_height / 2 });
+            let origin = CornellBox::view_plane_point(camera_setting, pixel);
+            ThinLens::autofocus(camera_setting, origin, &|ray| {
+                root.iter()
+                    .filter_map(|o| o.intersects(ray, 0.0, f64::INFINITY))
+                    .map(|hit| hit.dist)
+                    .reduce(f64::min)
+            })
+            .unwrap_or_else(|| (camera_setting.lookat - camera_setting.eye).norm())
+        });
+
+        Ok(Self { aperture, lens, focus_distance })
+    }
+}
+
 impl Scene for CornellBox {
     fn view_width(&self) -> u32 {
         self.view_width
@@ -169,6 +435,22 @@ impl Scene for CornellBox {
         &self.lights
     }
 
+    fn environment(&self) -> Option<&Arc<Environment>> {
+        self.environment.as_ref()
+    }
+
+    fn bvh_build_time(&self) -> Duration {
+        self.bvh_build_time
+    }
+
+    fn set_camera_key(&mut self, key: &CameraKey, args: &Args) -> Result<()> {
+        let view = (self.camera_setting.view_width, self.camera_setting.view_height);
+        let camera_setting = Self::camera_setting(key, view, args);
+        self.camera = Self::build_camera(camera_setting.clone(), args, &self.optics)?;
+        self.camera_setting = camera_setting;
+        Ok(())
+    }
+
     fn intersects(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
         self.intersects(ray, t_min, t_max)
     }
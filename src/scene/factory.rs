@@ -4,6 +4,8 @@
 //! configuration parameters, making it easier to add new scene types
 //! and manage scene creation logic.
 
+use serde::Serialize;
+
 use crate::{
     args::Args,
     error::Result,
@@ -28,9 +30,18 @@ impl SceneFactory {
     /// # Errors
     /// Returns an error if the scene cannot be created or assets cannot be loaded
     pub fn create_scene(args: &Args) -> Result<Box<dyn Scene>> {
-        // For now, only Cornell Box is supported
-        // Future: Add scene selection logic here based on args
-        Self::create_cornell_box(args)
+        match Self::scene_type(args) {
+            SceneType::CornellBox => Self::create_cornell_box(args),
+        }
+    }
+
+    /// Returns the type of scene that `create_scene` builds for the arguments.
+    ///
+    /// Currently only supports Cornell Box.
+    /// Future: Add scene selection logic here based on args
+    #[must_use]
+    pub const fn scene_type(_args: &Args) -> SceneType {
+        SceneType::CornellBox
     }
 
     /// Creates a Cornell Box scene.
@@ -52,7 +63,8 @@ impl SceneFactory {
 }
 
 /// Available scene types that can be created by the factory.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum SceneType {
     /// Classic Cornell Box scene for testing global illumination
     #[default]
@@ -16,11 +16,13 @@ pub use cornell_box::*;
 pub use factory::*;
 
 use crate::{
-    camera::Camera,
-    light::{Ambient, Light},
+    args::Args,
+    camera::{Camera, CameraKey},
+    error::Result,
+    light::{Ambient, Environment, Light},
     ray::{HitRecord, Ray},
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 /// Trait for all scenes that can be rendered.
 ///
@@ -45,6 +47,23 @@ pub trait Scene: Send + Sync {
     /// Returns all light sources in the scene.
     fn lights(&self) -> &[Arc<dyn Light>];
 
+    /// Returns the environment seen by rays leaving the scene, if any.
+    fn environment(&self) -> Option<&Arc<Environment>> {
+        None
+    }
+
+    /// Returns the time spent building the acceleration structure.
+    fn bvh_build_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// Reframes the camera with an animation keyframe, keeping the acceleration structure
+    /// and the camera's lens, aperture and focus distance.
+    ///
+    /// # Errors
+    /// Returns an error if the camera cannot be created
+    fn set_camera_key(&mut self, key: &CameraKey, args: &Args) -> Result<()>;
+
     /// Tests for ray-object intersection in the scene.
     ///
     /// # Arguments
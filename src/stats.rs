@@ -0,0 +1,153 @@
+//! Per-thread ray tracing counters.
+//!
+//! Counters live in thread-local storage so the hot intersection paths can
+//! bump them without any synchronization between Rayon worker threads.
+//! The renderer drains them with [`take`] after every image row and aggregates
+//! the result, see [`crate::renderer::Renderer::stats`].
+
+use std::{cell::Cell, ops::AddAssign};
+
+use serde::Serialize;
+
+/// The events that are counted while tracing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    /// Primary rays generated by the camera
+    CameraRays,
+    /// Secondary rays cast for reflections and refractions
+    ReflectionRays,
+    /// Shadow rays cast towards light sources
+    ShadowRays,
+    /// Hemisphere rays cast by the ambient occluder
+    OcclusionRays,
+    /// BVH nodes whose bounding box was hit and whose children were visited
+    BvhNodes,
+    /// Ray-AABB slab tests
+    AabbTests,
+    /// Ray-primitive intersection tests (spheres, triangles)
+    PrimitiveTests,
+}
+
+impl Counter {
+    /// Number of distinct counters
+    const COUNT: usize = 7;
+
+    /// All counters, in the order of their thread-local cells
+    const ALL: [Self; Self::COUNT] = [
+        Self::CameraRays,
+        Self::ReflectionRays,
+        Self::ShadowRays,
+        Self::OcclusionRays,
+        Self::BvhNodes,
+        Self::AabbTests,
+        Self::PrimitiveTests,
+    ];
+}
+
+/// A snapshot of all counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    /// Primary rays generated by the camera
+    pub camera_rays: u64,
+    /// Secondary rays cast for reflections and refractions
+    pub reflection_rays: u64,
+    /// Shadow rays cast towards light sources
+    pub shadow_rays: u64,
+    /// Hemisphere rays cast by the ambient occluder
+    pub occlusion_rays: u64,
+    /// BVH nodes visited
+    pub bvh_nodes: u64,
+    /// Ray-AABB slab tests
+    pub aabb_tests: u64,
+    /// Ray-primitive intersection tests
+    pub primitive_tests: u64,
+}
+
+impl Stats {
+    /// Returns the value of a single counter.
+    #[must_use]
+    pub const fn get(&self, counter: Counter) -> u64 {
+        match counter {
+            Counter::CameraRays => self.camera_rays,
+            Counter::ReflectionRays => self.reflection_rays,
+            Counter::ShadowRays => self.shadow_rays,
+            Counter::OcclusionRays => self.occlusion_rays,
+            Counter::BvhNodes => self.bvh_nodes,
+            Counter::AabbTests => self.aabb_tests,
+            Counter::PrimitiveTests => self.primitive_tests,
+        }
+    }
+
+    const fn get_mut(&mut self, counter: Counter) -> &mut u64 {
+        match counter {
+            Counter::CameraRays => &mut self.camera_rays,
+            Counter::ReflectionRays => &mut self.reflection_rays,
+            Counter::ShadowRays => &mut self.shadow_rays,
+            Counter::OcclusionRays => &mut self.occlusion_rays,
+            Counter::BvhNodes => &mut self.bvh_nodes,
+            Counter::AabbTests => &mut self.aabb_tests,
+            Counter::PrimitiveTests => &mut self.primitive_tests,
+        }
+    }
+
+    /// Returns the number of rays of all kinds that were traced.
+    #[must_use]
+    pub const fn total_rays(&self) -> u64 {
+        self.camera_rays + self.reflection_rays + self.shadow_rays + self.occlusion_rays
+    }
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.camera_rays += rhs.camera_rays;
+        self.reflection_rays += rhs.reflection_rays;
+        self.shadow_rays += rhs.shadow_rays;
+        self.occlusion_rays += rhs.occlusion_rays;
+        self.bvh_nodes += rhs.bvh_nodes;
+        self.aabb_tests += rhs.aabb_tests;
+        self.primitive_tests += rhs.primitive_tests;
+    }
+}
+
+thread_local! {
+    static LOCAL: [Cell<u64>; Counter::COUNT] =
+        const { [const { Cell::new(0) }; Counter::COUNT] };
+}
+
+/// Increments the given counter on the current thread.
+#[inline]
+pub fn increment(counter: Counter) {
+    add(counter, 1);
+}
+
+/// Adds `n` to the given counter on the current thread.
+#[inline]
+pub fn add(counter: Counter, n: u64) {
+    LOCAL.with(|local| {
+        let cell = &local[counter as usize];
+        cell.set(cell.get() + n);
+    });
+}
+
+/// Returns the current value of the given counter on the current thread.
+#[must_use]
+pub fn get(counter: Counter) -> u64 {
+    LOCAL.with(|local| local[counter as usize].get())
+}
+
+/// Returns the counters of the current thread and resets them to zero.
+#[must_use]
+pub fn take() -> Stats {
+    LOCAL.with(|local| {
+        let mut stats = Stats::default();
+        for counter in Counter::ALL {
+            *stats.get_mut(counter) = local[counter as usize].take();
+        }
+        stats
+    })
+}
+
+/// Resets the counters of the current thread to zero, discarding their values.
+pub fn reset() {
+    LOCAL.with(|local| local.iter().for_each(|cell| cell.set(0)));
+}
@@ -0,0 +1,80 @@
+//! Image textures looked up by texture coordinates.
+//!
+//! Texels are read as linear colors in [0, 1], like the rendered image is written.
+
+use std::path::Path;
+
+use image::RgbImage;
+use nalgebra::Point2;
+
+use crate::{
+    color::Color,
+    error::{RayTracingError, Result},
+};
+
+/// An RGB image sampled with bilinear filtering.
+///
+/// Texture coordinates run from (0, 0) at the top left corner of the image
+/// to (1, 1) at the bottom right corner.
+pub struct ImageTexture {
+    width: u32,
+    height: u32,
+    /// Texels row by row from the top
+    texels: Vec<Color>,
+}
+
+impl ImageTexture {
+    /// Loads a texture from an image file.
+    ///
+    /// # Errors
+    /// Returns an error if the image cannot be loaded
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let image = image::open(path).map_err(|e| {
+            RayTracingError::AssetError(format!("Failed to load texture '{}': {e}", path.display()))
+        })?;
+        Ok(Self::from_image(&image.to_rgb8()))
+    }
+
+    /// Creates a texture from an RGB image.
+    #[must_use]
+    pub fn from_image(image: &RgbImage) -> Self {
+        let texels = image
+            .pixels()
+            .map(|p| Color::new(f64::from(p.0[0]), f64::from(p.0[1]), f64::from(p.0[2])) / 255.0)
+            .collect();
+        Self { width: image.width(), height: image.height(), texels }
+    }
+
+    /// Returns the width and height of the image in texels.
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the texel at column `x` and row `y`, clamped to the image.
+    fn texel(&self, x: i64, y: i64) -> Color {
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (x, y) = (
+            x.clamp(0, i64::from(self.width) - 1) as usize,
+            y.clamp(0, i64::from(self.height) - 1) as usize,
+        );
+        self.texels[y * self.width as usize + x]
+    }
+
+    /// Returns the bilinearly filtered color at texture coordinates `uv`.
+    ///
+    /// Coordinates outside [0, 1]² repeat the texels at the edge.
+    #[must_use]
+    pub fn sample(&self, uv: Point2<f64>) -> Color {
+        // Texel centers lie at half-integer coordinates
+        let x = uv.x.mul_add(f64::from(self.width), -0.5);
+        let y = uv.y.mul_add(f64::from(self.height), -0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        #[expect(clippy::cast_possible_truncation)]
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let top = self.texel(x0, y0).lerp(&self.texel(x0 + 1, y0), tx);
+        let bottom = self.texel(x0, y0 + 1).lerp(&self.texel(x0 + 1, y0 + 1), tx);
+        top.lerp(&bottom, ty)
+    }
+}
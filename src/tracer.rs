@@ -0,0 +1,115 @@
+//! Debug visualisation tracers.
+//!
+//! These tracers replace material shading for primary rays and visualise
+//! properties of the first hit instead, which helps to find broken meshes,
+//! flipped normals and slow parts of the acceleration structure.
+//!
+//! Depth and heat map tracers produce raw values that only make sense relative
+//! to the rest of the image, so [`normalize`] rescales them after rendering.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::{
+    args::ArgTracer,
+    color::Color,
+    config::{
+        bvh::{INTERSECTION_COST, TRAVERSAL_COST},
+        tracer::WIREFRAME_THICKNESS,
+    },
+    ray::{HitRecord, Ray},
+    renderer::Renderer,
+    stats::{self, Counter},
+};
+
+/// Traces a primary ray with one of the debug tracers.
+///
+/// # Arguments
+/// * `renderer` - The renderer holding the scene
+/// * `ray` - The camera ray to trace
+/// * `tracer` - The debug visualisation to produce
+///
+/// # Returns
+/// The debug color, or a raw value for tracers that are normalized afterwards
+#[must_use]
+pub fn trace(renderer: &Renderer, ray: &Ray, tracer: ArgTracer) -> Color {
+    let nodes = stats::get(Counter::BvhNodes);
+    let primitives = stats::get(Counter::PrimitiveTests);
+
+    let record = renderer.scene.intersects(ray, 0.0, f64::INFINITY);
+
+    if tracer == ArgTracer::BvhHeatmap {
+        #[expect(clippy::cast_precision_loss)]
+        let nodes = (stats::get(Counter::BvhNodes) - nodes) as f64;
+        #[expect(clippy::cast_precision_loss)]
+        let primitives = (stats::get(Counter::PrimitiveTests) - primitives) as f64;
+        return Color::repeat(nodes * TRAVERSAL_COST + primitives * INTERSECTION_COST);
+    }
+
+    record.map_or_else(Color::zeros, |record| shade(ray, &record, tracer))
+}
+
+/// Visualises a single hit record.
+fn shade(ray: &Ray, record: &HitRecord, tracer: ArgTracer) -> Color {
+    match tracer {
+        ArgTracer::Normals => {
+            // Face the normal towards the camera, as the shading tracer does
+            let normal = record.normal * record.normal.dot(&-ray.dir).signum();
+            (normal + Color::repeat(1.0)) * 0.5
+        }
+        ArgTracer::Depth => Color::repeat(record.dist),
+        ArgTracer::Barycentrics => {
+            Color::new(1.0 - record.uv.x - record.uv.y, record.uv.x, record.uv.y)
+        }
+        ArgTracer::Wireframe => {
+            let edge = record.uv.x.min(record.uv.y).min(1.0 - record.uv.x - record.uv.y);
+            if edge < WIREFRAME_THICKNESS { Color::repeat(1.0) } else { Color::repeat(0.1) }
+        }
+        ArgTracer::MaterialId => material_color(record),
+        ArgTracer::Whitted | ArgTracer::BvhHeatmap => Color::zeros(),
+    }
+}
+
+/// Picks a stable false color for a material.
+///
+/// Materials are identified by their albedo and emission,
+/// so surfaces sharing identical material parameters share a color.
+fn material_color(record: &HitRecord) -> Color {
+    let mut hasher = DefaultHasher::new();
+    for c in &record.material.albedo() {
+        c.to_bits().hash(&mut hasher);
+    }
+    record.material.emissive().hash(&mut hasher);
+    let hash = hasher.finish().to_le_bytes();
+    Color::new(f64::from(hash[0]), f64::from(hash[1]), f64::from(hash[2])) / 255.0
+}
+
+/// Rescales raw tracer output into displayable colors.
+///
+/// Depth values are divided by the largest depth in the image,
+/// and heat map costs are mapped onto a blue to red color ramp.
+pub fn normalize(tracer: ArgTracer, pixels: &mut [Color]) {
+    if !matches!(tracer, ArgTracer::Depth | ArgTracer::BvhHeatmap) {
+        return;
+    }
+
+    let max = pixels.iter().map(|c| c.x).fold(0.0, f64::max);
+    if max <= 0.0 {
+        return;
+    }
+
+    for pixel in pixels {
+        let t = pixel.x / max;
+        *pixel = if tracer == ArgTracer::Depth { Color::repeat(t) } else { heat(t) };
+    }
+}
+
+/// Maps `t` in [0, 1] onto a blue, cyan, green, yellow, red ramp.
+fn heat(t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0) * 4.0;
+    match t {
+        t if t < 1.0 => Color::new(0.0, t, 1.0),
+        t if t < 2.0 => Color::new(0.0, 1.0, 2.0 - t),
+        t if t < 3.0 => Color::new(t - 2.0, 1.0, 0.0),
+        t => Color::new(1.0, 4.0 - t, 0.0),
+    }
+}